@@ -0,0 +1,186 @@
+use crate::exam::parse_clock_time;
+use crate::timetable::{PeriodRange, Session};
+use chrono::NaiveTime;
+use std::collections::{BTreeMap, HashMap};
+use std::{env, fmt, fs};
+
+/// Start and end of a class, in local (UTC+7) time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeSpan {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl fmt::Display for TimeSpan {
+    /// Uses the portal's notation, e.g. "7g00 - 9g15".
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} - {}",
+            self.start.format("%-Hg%M"),
+            self.end.format("%-Hg%M")
+        )
+    }
+}
+
+/// Maps each period ("tiết") of each session to its clock time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BellSchedule {
+    periods: HashMap<Session, BTreeMap<u8, TimeSpan>>,
+}
+
+fn span(start: (u32, u32), end: (u32, u32)) -> TimeSpan {
+    TimeSpan {
+        start: NaiveTime::from_hms_opt(start.0, start.1, 0).unwrap(),
+        end: NaiveTime::from_hms_opt(end.0, end.1, 0).unwrap(),
+    }
+}
+
+impl Default for BellSchedule {
+    /// UDA's bell times for morning and afternoon classes: 45-minute periods
+    /// with a 30-minute break after period 3. Evening periods have no
+    /// published times and are left out, so they resolve to `None` unless a
+    /// `UDA_BELL_SCHEDULE` file supplies them.
+    fn default() -> Self {
+        let morning = [
+            span((7, 0), (7, 45)),
+            span((7, 45), (8, 30)),
+            span((8, 30), (9, 15)),
+            span((9, 45), (10, 30)),
+            span((10, 30), (11, 15)),
+            span((11, 15), (12, 0)),
+        ];
+        let afternoon = [
+            span((13, 0), (13, 45)),
+            span((13, 45), (14, 30)),
+            span((14, 30), (15, 15)),
+            span((15, 45), (16, 30)),
+            span((16, 30), (17, 15)),
+            span((17, 15), (18, 0)),
+        ];
+
+        let number = |spans: &[TimeSpan]| {
+            spans
+                .iter()
+                .enumerate()
+                .map(|(i, s)| (i as u8 + 1, *s))
+                .collect::<BTreeMap<_, _>>()
+        };
+
+        Self {
+            periods: HashMap::from([
+                (Session::Morning, number(&morning)),
+                (Session::Afternoon, number(&afternoon)),
+            ]),
+        }
+    }
+}
+
+impl BellSchedule {
+    /// Loads the default schedule, overridden by the file named in
+    /// `UDA_BELL_SCHEDULE` if it is set (see [`BellSchedule::parse`]).
+    /// An unreadable or invalid file is reported and ignored.
+    pub fn from_env() -> Self {
+        let Ok(path) = env::var("UDA_BELL_SCHEDULE") else {
+            return Self::default();
+        };
+        match fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|s| Self::parse(&s))
+        {
+            Ok(schedule) => schedule,
+            Err(e) => {
+                eprintln!("Warning: ignoring bell schedule {}: {}", path, e);
+                Self::default()
+            }
+        }
+    }
+
+    /// Parses bell times on top of the default schedule. One period per line:
+    ///
+    /// ```text
+    /// # session period start-end
+    /// Tối 1 18g00-18g45
+    /// Tối 2 18g45-19g30
+    /// ```
+    ///
+    /// Blank lines and lines starting with `#` are ignored.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let mut schedule = Self::default();
+        for (n, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = || {
+                format!(
+                    "line {}: expected `<session> <period> <start>-<end>`",
+                    n + 1
+                )
+            };
+
+            let mut parts = line.split_whitespace();
+            let session = parts.next().and_then(Session::parse).ok_or_else(invalid)?;
+            let period: u8 = parts
+                .next()
+                .and_then(|p| p.parse().ok())
+                .ok_or_else(invalid)?;
+            let times: String = parts.collect();
+            let (start, end) = times.split_once('-').ok_or_else(invalid)?;
+            let (Some(start), Some(end)) = (parse_clock_time(start), parse_clock_time(end)) else {
+                return Err(invalid());
+            };
+            if start >= end {
+                return Err(format!("line {}: period ends before it starts", n + 1));
+            }
+            schedule.set(session, period, TimeSpan { start, end });
+        }
+        Ok(schedule)
+    }
+
+    pub fn set(&mut self, session: Session, period: u8, span: TimeSpan) {
+        self.periods
+            .entry(session)
+            .or_default()
+            .insert(period, span);
+    }
+
+    /// Clock time of a single period.
+    pub fn period(&self, session: Session, period: u8) -> Option<TimeSpan> {
+        self.periods.get(&session)?.get(&period).copied()
+    }
+
+    /// Clock time of a period range: the start of its first period to the end
+    /// of its last one. `None` if either end isn't in the schedule.
+    pub fn resolve(&self, session: Session, periods: &PeriodRange) -> Option<TimeSpan> {
+        Some(TimeSpan {
+            start: self.period(session, periods.start)?.start,
+            end: self.period(session, periods.end)?.end,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_has_no_evening_periods() {
+        let bells = BellSchedule::default();
+        assert_eq!(bells.period(Session::Evening, 1), None);
+        let span = bells
+            .resolve(Session::Afternoon, &PeriodRange { start: 4, end: 6 })
+            .unwrap();
+        assert_eq!(span.to_string(), "15g45 - 18g00");
+    }
+
+    #[test]
+    fn parse_adds_evening_periods() {
+        let bells = BellSchedule::parse("# session period start-end\nTối 1 18g00-18g45\n").unwrap();
+        assert_eq!(
+            bells.period(Session::Evening, 1).map(|s| s.to_string()),
+            Some("18g00 - 18g45".to_string())
+        );
+        assert!(BellSchedule::parse("Tối 1 19g00-18g00").is_err());
+    }
+}
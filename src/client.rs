@@ -0,0 +1,696 @@
+use crate::error::Error;
+use crate::exam::{parse_exams, ExamEntry};
+use crate::login::{check_login_response, is_login_page, LoginError};
+use crate::notice::{find_dates, parse_cancellation_notices, CancellationNotice};
+use crate::retry::{is_transient, Reporter, RequestEvent, RetryPolicy};
+use crate::session::{load_cookies, save_cookies, CookieJar};
+use crate::timetable::{parse_timetable, ClassSession};
+use crate::upcoming::{parse_upcoming_schedule, UpcomingClass};
+use crate::webforms::{Dropdown, FormState};
+use chrono::{Duration, NaiveDate};
+use reqwest::cookie::CookieStore;
+use reqwest::{Client, Response, StatusCode, Url};
+use scraper::{Html, Selector};
+use std::io;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration as StdDuration, Instant};
+use tokio::sync::Mutex;
+
+const USER_AGENT: &str = "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36";
+
+/// The login page, relative to `/sv/`. Requests for it never count as an
+/// expired session.
+const LOGIN_PAGE: &str = "svlogin";
+
+/// Portal used unless another base URL is configured.
+pub const DEFAULT_BASE_URL: &str = "https://my.uda.edu.vn/";
+
+/// Parses a portal base URL such as "https://my.uda.edu.vn" or
+/// "http://localhost:8080/uda". The student pages live under `sv/` below it.
+pub fn parse_base_url(s: &str) -> Result<Url, String> {
+    let mut url = Url::parse(s.trim()).map_err(|e| format!("invalid base URL `{}`: {}", s, e))?;
+    if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() {
+        return Err(format!("invalid base URL `{}`: expected http(s)://host", s));
+    }
+    if !url.path().ends_with('/') {
+        let path = format!("{}/", url.path());
+        url.set_path(&path);
+    }
+    url.set_query(None);
+    url.set_fragment(None);
+    Ok(url)
+}
+
+/// Some setups answer requests of an expired session with 401 or 403
+/// instead of redirecting to the login page.
+fn is_unauthorized(status: StatusCode) -> bool {
+    status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN
+}
+
+/// Every portal page has controls of the `MainContent` placeholder; a page
+/// without any is not the page that was asked for.
+const CONTENT: &str = "[id^=MainContent_]";
+
+fn require_content(html: &Html, page: &str) -> Result<(), Error> {
+    match html.select(&Selector::parse(CONTENT).unwrap()).next() {
+        Some(_) => Ok(()),
+        None => Err(Error::layout(page, CONTENT)),
+    }
+}
+
+/// Everything parsed from the timetable page (`/sv/tkb`). The two grids are
+/// parsed independently, so one that can't be read doesn't hide the other.
+#[derive(Debug)]
+pub struct TimetablePage {
+    pub sessions: Result<Vec<ClassSession>, Error>,
+    pub upcoming: Result<Vec<UpcomingClass>, Error>,
+    pub notices: Vec<CancellationNotice>,
+    /// Rows of either grid that couldn't be read and were left out.
+    pub warnings: Vec<Error>,
+}
+
+impl TimetablePage {
+    /// Parses the text of a `/sv/tkb` page. Relative links are resolved
+    /// against `page_url`. Fails only if this isn't a portal page at all.
+    pub fn parse(text: &str, page_url: &Url) -> Result<Self, Error> {
+        let html = Html::parse_document(text);
+        require_content(&html, "tkb")?;
+        let tr = Selector::parse("tr").unwrap();
+        let td = Selector::parse("td").unwrap();
+        let mut warnings = Vec::new();
+        Ok(Self {
+            sessions: parse_timetable(&html, &tr, &td, page_url, &mut warnings),
+            upcoming: parse_upcoming_schedule(&html, &tr, &td, &mut warnings),
+            notices: parse_cancellation_notices(&html, &tr, &td),
+            warnings,
+        })
+    }
+}
+
+/// The exam schedule page (`/sv/lichthi`).
+#[derive(Debug)]
+pub struct ExamPage {
+    pub exams: Vec<ExamEntry>,
+    /// Rows that couldn't be read and were left out.
+    pub warnings: Vec<Error>,
+}
+
+/// Which timetable to fetch. The default is whatever week and semester the
+/// portal shows first.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TimetableQuery {
+    /// Any date in the wanted week.
+    pub week: Option<NaiveDate>,
+    /// Semester id or name as listed in the portal's semester picker.
+    pub semester: Option<String>,
+}
+
+/// The week picker option covering `date`: an option listing a date range
+/// ("Tuần 5 (01/09/2025 - 07/09/2025)") that contains it, or one listing a
+/// single start date of the week. Returns `(dropdown name, option value)`.
+fn week_option(dropdowns: &[Dropdown], date: NaiveDate) -> Option<(String, String)> {
+    dropdowns.iter().find_map(|dropdown| {
+        dropdown.options.iter().find_map(|(value, text)| {
+            let mut dates = find_dates(text);
+            if dates.is_empty() {
+                dates = find_dates(value);
+            }
+            let start = *dates.first()?;
+            let end = match dates.last() {
+                Some(&end) if end > start => end,
+                _ => start + Duration::days(6),
+            };
+            (start <= date && date <= end).then(|| (dropdown.name.clone(), value.clone()))
+        })
+    })
+}
+
+/// The semester picker option whose value equals `id` or whose text contains
+/// it (ignoring case). Week pickers, whose options list dates, are skipped.
+/// Returns `(dropdown name, option value)`.
+fn semester_option(dropdowns: &[Dropdown], id: &str) -> Option<(String, String)> {
+    let id = id.trim().to_lowercase();
+    let is_week_picker = |d: &&Dropdown| {
+        d.options
+            .iter()
+            .any(|(_, text)| !find_dates(text).is_empty())
+    };
+    dropdowns
+        .iter()
+        .filter(|d| !is_week_picker(d))
+        .find_map(|dropdown| {
+            dropdown.options.iter().find_map(|(value, text)| {
+                (value.to_lowercase() == id || text.to_lowercase().contains(&id))
+                    .then(|| (dropdown.name.clone(), value.clone()))
+            })
+        })
+}
+
+/// Async client for the student portal. It keeps the session cookie from
+/// [`UdaClient::login`] for the requests that follow, and logs in again with
+/// the same credentials when the portal expires the session mid-run.
+///
+/// # Examples
+/// ```no_run
+/// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+/// let client = request::UdaClient::new()?;
+/// client.login("student_id", "password").await?;
+/// let page = client.fetch_timetable().await?;
+/// println!("{} classes", page.sessions?.len());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct UdaClient {
+    client: Client,
+    /// Base of the student pages, `<base URL>/sv/`.
+    pages: Url,
+    cookies: Arc<CookieJar>,
+    cookie_file: Option<PathBuf>,
+    retry: RetryPolicy,
+    deadline: Option<Instant>,
+    reporter: Option<Reporter>,
+    /// Shared between clones, so concurrent requests that all find the
+    /// session expired log in only once.
+    session: Arc<Mutex<Session>>,
+}
+
+/// Credentials to log in again with, and how many logins have happened so
+/// far.
+#[derive(Debug, Default)]
+struct Session {
+    credentials: Option<(String, String)>,
+    logins: u64,
+}
+
+/// Builder for [`UdaClient`], from [`UdaClient::builder`].
+#[derive(Debug, Clone)]
+pub struct UdaClientBuilder {
+    base_url: Url,
+    cookie_file: Option<PathBuf>,
+    retry: RetryPolicy,
+    reporter: Option<Reporter>,
+}
+
+impl UdaClientBuilder {
+    /// Portal to talk to instead of [`DEFAULT_BASE_URL`], e.g. a mirror or a
+    /// local stand-in server (see [`parse_base_url`]).
+    pub fn base_url(mut self, url: Url) -> Self {
+        self.base_url = url;
+        self
+    }
+
+    /// Loads session cookies from, and saves them to, `path` (see
+    /// [`default_cookie_path`](crate::default_cookie_path)). An unreadable
+    /// file is reported and ignored.
+    pub fn cookie_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.cookie_file = Some(path.into());
+        self
+    }
+
+    /// Timeouts, retries and deadline to use instead of
+    /// [`RetryPolicy::default`]. The deadline starts when the client is
+    /// built.
+    pub fn retry(mut self, policy: RetryPolicy) -> Self {
+        self.retry = policy;
+        self
+    }
+
+    /// Calls `report` before every request attempt and before waiting to
+    /// retry one, e.g. to show progress.
+    pub fn on_request(mut self, report: impl Fn(RequestEvent<'_>) + Send + Sync + 'static) -> Self {
+        self.reporter = Some(Reporter(Arc::new(report)));
+        self
+    }
+
+    pub fn build(self) -> Result<UdaClient, Error> {
+        let pages = self.base_url.join("sv/").unwrap();
+        let cookies = match &self.cookie_file {
+            Some(path) => load_cookies(path).unwrap_or_else(|e| {
+                eprintln!("Warning: ignoring cookie file {}: {}", path.display(), e);
+                CookieJar::default()
+            }),
+            None => CookieJar::default(),
+        };
+        let cookies = Arc::new(cookies);
+
+        let client = Client::builder()
+            .cookie_provider(cookies.clone())
+            .build()
+            .map_err(|e| Error::config("HTTP client", e.to_string()))?;
+        Ok(UdaClient {
+            client,
+            pages,
+            cookies,
+            cookie_file: self.cookie_file,
+            retry: self.retry,
+            // A deadline too far off to represent is no deadline
+            deadline: self
+                .retry
+                .deadline
+                .and_then(|d| Instant::now().checked_add(d)),
+            reporter: self.reporter,
+            session: Arc::default(),
+        })
+    }
+}
+
+impl UdaClient {
+    /// A client for [`DEFAULT_BASE_URL`] with an in-memory session only.
+    pub fn new() -> Result<Self, Error> {
+        Self::builder().build()
+    }
+
+    pub fn builder() -> UdaClientBuilder {
+        UdaClientBuilder {
+            base_url: Url::parse(DEFAULT_BASE_URL).unwrap(),
+            cookie_file: None,
+            retry: RetryPolicy::default(),
+            reporter: None,
+        }
+    }
+
+    /// Writes the current session cookies to the cookie file, if the client
+    /// has one.
+    pub fn save_session(&self) -> io::Result<()> {
+        match &self.cookie_file {
+            Some(path) => save_cookies(&self.cookies, path),
+            None => Ok(()),
+        }
+    }
+
+    /// Reuses the saved session if there is one, otherwise logs in and saves
+    /// the new session. Returns `true` when the saved session is reused.
+    ///
+    /// The saved session isn't checked up front: if the portal has expired
+    /// it, the first request logs in again with these credentials (see
+    /// [`UdaClient::login`]).
+    pub async fn resume_session(&self, username: &str, password: &str) -> Result<bool, Error> {
+        if self.cookies.cookies(&self.pages).is_some() {
+            self.session.lock().await.credentials =
+                Some((username.to_string(), password.to_string()));
+            return Ok(true);
+        }
+
+        self.login(username, password).await?;
+        self.save_session_or_warn();
+        Ok(false)
+    }
+
+    fn save_session_or_warn(&self) {
+        if let Err(e) = self.save_session() {
+            eprintln!("Warning: could not save session cookies: {}", e);
+        }
+    }
+
+    /// Logs in again after a request found the session expired, unless a
+    /// concurrent request already did since `logins` was read. Returns
+    /// `false` if there are no credentials to log in with.
+    async fn relogin(&self, logins: u64) -> Result<bool, Error> {
+        let mut session = self.session.lock().await;
+        if session.logins != logins {
+            return Ok(true);
+        }
+        let Some((username, password)) = session.credentials.clone() else {
+            return Ok(false);
+        };
+        // The expired session's cookies would otherwise be sent along with,
+        // and saved next to, the new ones
+        self.cookies.clear();
+        // Boxed, as logging in goes through `get` and `post_form`
+        Box::pin(self.sign_in(&username, &password)).await?;
+        session.logins += 1;
+        drop(session);
+        self.save_session_or_warn();
+        Ok(true)
+    }
+
+    /// Number of logins so far, read before a request so [`UdaClient::relogin`]
+    /// can tell whether another request has logged in again meanwhile.
+    async fn logins(&self) -> u64 {
+        self.session.lock().await.logins
+    }
+
+    fn url(&self, page: &str) -> Url {
+        self.pages.join(page).unwrap()
+    }
+
+    fn report(&self, event: RequestEvent<'_>) {
+        if let Some(reporter) = &self.reporter {
+            (reporter.0)(event);
+        }
+    }
+
+    /// Timeout for the next request: the policy's, cut short by the
+    /// deadline.
+    fn request_timeout(&self, page: &str) -> Result<StdDuration, Error> {
+        let Some(deadline) = self.deadline else {
+            return Ok(self.retry.timeout);
+        };
+        let left = deadline.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            return Err(self.deadline_error(page));
+        }
+        Ok(left.min(self.retry.timeout))
+    }
+
+    fn deadline_error(&self, page: &str) -> Error {
+        Error::Deadline {
+            page: page.to_string(),
+            deadline: self.retry.deadline.unwrap_or_default(),
+        }
+    }
+
+    /// A request failure, reported as the deadline passing if it timed out
+    /// because of it.
+    fn failed(&self, page: &str, e: reqwest::Error) -> Error {
+        let past_deadline = self.deadline.is_some_and(|d| Instant::now() >= d);
+        if e.is_timeout() && past_deadline {
+            self.deadline_error(page)
+        } else {
+            Error::network(page, e)
+        }
+    }
+
+    /// GETs `page`, retrying transient failures as the [`RetryPolicy`]
+    /// allows. Returns the final URL (after redirects) and the body.
+    ///
+    /// If the portal answers with the login page (or 401/403), the session
+    /// has expired: the client logs in again and repeats the request once.
+    async fn get(&self, page: &str) -> Result<(Url, String), Error> {
+        // Logging in holds the session lock
+        if page == LOGIN_PAGE {
+            return self.get_retrying(page).await;
+        }
+        let logins = self.logins().await;
+        let result = self.get_retrying(page).await;
+        let expired = match &result {
+            Ok((url, body)) => is_login_page(url, body),
+            Err(Error::Network { source, .. }) => source.status().is_some_and(is_unauthorized),
+            Err(_) => false,
+        };
+        if !expired || !self.relogin(logins).await? {
+            return result;
+        }
+        self.get_retrying(page).await
+    }
+
+    async fn get_retrying(&self, page: &str) -> Result<(Url, String), Error> {
+        let attempts = self.retry.retries.saturating_add(1);
+        let mut attempt = 1;
+        loop {
+            let timeout = self.request_timeout(page)?;
+            self.report(RequestEvent::Sending {
+                page,
+                attempt,
+                attempts,
+            });
+            let e = match self.try_get(page, timeout).await {
+                Ok(result) => return Ok(result),
+                Err(e) => e,
+            };
+            if attempt == attempts || !is_transient(&e) {
+                return Err(self.failed(page, e));
+            }
+
+            let delay = self.retry.delay(attempt);
+            if self.deadline.is_some_and(|d| Instant::now() + delay >= d) {
+                return Err(self.deadline_error(page));
+            }
+            self.report(RequestEvent::Retrying {
+                page,
+                attempt,
+                attempts,
+                reason: &e,
+                delay,
+            });
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    async fn try_get(&self, page: &str, timeout: StdDuration) -> reqwest::Result<(Url, String)> {
+        let resp = self
+            .client
+            .get(self.url(page))
+            .timeout(timeout)
+            .send()
+            .await?
+            .error_for_status()?;
+        let url = resp.url().clone();
+        Ok((url, resp.text_with_charset("utf-8").await?))
+    }
+
+    /// Fetches the login page for its WebForms state, then posts the login
+    /// form to `/sv/svlogin` and checks that the portal actually let us in.
+    /// The credentials are kept to log in again if the session expires.
+    pub async fn login(&self, username: &str, password: &str) -> Result<(), Error> {
+        let mut session = self.session.lock().await;
+        self.sign_in(username, password).await?;
+        session.credentials = Some((username.to_string(), password.to_string()));
+        session.logins += 1;
+        Ok(())
+    }
+
+    async fn sign_in(&self, username: &str, password: &str) -> Result<(), Error> {
+        let (_, state) = self.fetch_form(LOGIN_PAGE).await?;
+        let state = state.with("User", username).with("Password", password);
+
+        let resp = self
+            .post_form(LOGIN_PAGE, &state.postback("Lnew1", ""))
+            .await?;
+
+        if resp.status() == StatusCode::SERVICE_UNAVAILABLE {
+            return Err(LoginError::Maintenance.into());
+        }
+        let final_url = resp.url().clone();
+        let body = self.read_text(LOGIN_PAGE, resp).await?;
+        Ok(check_login_response(&final_url, &body)?)
+    }
+
+    /// POSTs a form to `page`. Not retried, as a postback changes the
+    /// portal's state, except once after logging in again if it was
+    /// redirected to the login page because the session had expired.
+    async fn post_form(&self, page: &str, fields: &[(String, String)]) -> Result<Response, Error> {
+        if page == LOGIN_PAGE {
+            return self.send_form(page, fields).await;
+        }
+        let logins = self.logins().await;
+        let resp = self.send_form(page, fields).await?;
+        let expired =
+            resp.url().path().to_lowercase().contains(LOGIN_PAGE) || is_unauthorized(resp.status());
+        if !expired || !self.relogin(logins).await? {
+            return Ok(resp);
+        }
+        self.send_form(page, fields).await
+    }
+
+    async fn send_form(&self, page: &str, fields: &[(String, String)]) -> Result<Response, Error> {
+        let timeout = self.request_timeout(page)?;
+        self.report(RequestEvent::Sending {
+            page,
+            attempt: 1,
+            attempts: 1,
+        });
+        let url = self.url(page);
+        let origin = url.origin().ascii_serialization();
+        let host = match url.port() {
+            Some(port) => format!("{}:{}", url.host_str().unwrap_or(""), port),
+            None => url.host_str().unwrap_or("").to_string(),
+        };
+        self.client
+            .post(url.clone())
+            .header("Content-Type", "application/x-www-form-urlencoded")
+            .header("Host", host)
+            .header("Origin", origin)
+            .header("Referer", url.as_str())
+            .header("User-Agent", USER_AGENT)
+            .header("Connection", "keep-alive")
+            .form(fields)
+            .timeout(timeout)
+            .send()
+            .await
+            .map_err(|e| self.failed(page, e))
+    }
+
+    /// Body of a successful response as UTF-8 text.
+    async fn read_text(&self, page: &str, resp: Response) -> Result<String, Error> {
+        let text = match resp.error_for_status() {
+            Ok(resp) => resp.text_with_charset("utf-8").await,
+            Err(e) => Err(e),
+        };
+        text.map_err(|e| self.failed(page, e))
+    }
+
+    /// Fetches a page and returns its text together with its [`FormState`].
+    pub async fn fetch_form(&self, page: &str) -> Result<(String, FormState), Error> {
+        let text = self.fetch_page(page).await?;
+        let state = FormState::from_html(&Html::parse_document(&text));
+        Ok((text, state))
+    }
+
+    /// Replays `state` to `page` as a `__doPostBack(target, argument)` and
+    /// returns the resulting page's text and form state, ready for the next
+    /// postback.
+    pub async fn postback(
+        &self,
+        page: &str,
+        state: &FormState,
+        target: &str,
+        argument: &str,
+    ) -> Result<(String, FormState), Error> {
+        let resp = self
+            .post_form(page, &state.postback(target, argument))
+            .await?;
+        let text = self.read_text(page, resp).await?;
+        let state = FormState::from_html(&Html::parse_document(&text));
+        Ok((text, state))
+    }
+
+    /// Fetches a portal page (relative to `/sv/`) as UTF-8 text, retrying
+    /// as the client's [`RetryPolicy`] allows.
+    pub async fn fetch_page(&self, page: &str) -> Result<String, Error> {
+        Ok(self.get(page).await?.1)
+    }
+
+    /// Fetches `/sv/tkb` and parses the official timetable, the upcoming
+    /// schedule and the cancellation notices on it.
+    pub async fn fetch_timetable(&self) -> Result<TimetablePage, Error> {
+        let text = self.fetch_page("tkb").await?;
+        TimetablePage::parse(&text, &self.url("tkb"))
+    }
+
+    /// Like [`UdaClient::fetch_timetable`], but first drives the portal's
+    /// semester and week pickers through postbacks to show the timetable
+    /// `query` asks for.
+    pub async fn fetch_timetable_for(
+        &self,
+        query: &TimetableQuery,
+    ) -> Result<TimetablePage, Error> {
+        let (mut text, mut state) = self.fetch_form("tkb").await?;
+
+        if let Some(id) = &query.semester {
+            let dropdowns = Dropdown::find_all(&Html::parse_document(&text));
+            let (name, value) =
+                semester_option(&dropdowns, id).ok_or_else(|| Error::NoSuchSemester(id.clone()))?;
+            (text, state) = self
+                .postback("tkb", &state.with(&name, &value), &name, "")
+                .await?;
+        }
+
+        if let Some(date) = query.week {
+            let dropdowns = Dropdown::find_all(&Html::parse_document(&text));
+            let (name, value) = week_option(&dropdowns, date).ok_or(Error::NoSuchWeek(date))?;
+            (text, _) = self
+                .postback("tkb", &state.with(&name, &value), &name, "")
+                .await?;
+        }
+
+        TimetablePage::parse(&text, &self.url("tkb"))
+    }
+
+    /// Fetches `/sv/lichthi` and parses the exam schedule.
+    pub async fn fetch_exams(&self) -> Result<ExamPage, Error> {
+        let text = self.fetch_page("lichthi").await?;
+
+        let html = Html::parse_document(&text);
+        require_content(&html, "lichthi")?;
+        let tr = Selector::parse("tr").unwrap();
+        let td = Selector::parse("td").unwrap();
+        let mut warnings = Vec::new();
+        let exams = parse_exams(&html, &tr, &td, &mut warnings)?;
+        Ok(ExamPage { exams, warnings })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dropdown(name: &str, options: &[(&str, &str)]) -> Dropdown {
+        Dropdown {
+            name: name.to_string(),
+            options: options
+                .iter()
+                .map(|(value, text)| (value.to_string(), text.to_string()))
+                .collect(),
+        }
+    }
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%d/%m/%Y").unwrap()
+    }
+
+    fn pickers() -> Vec<Dropdown> {
+        vec![
+            dropdown(
+                "cboHocKy",
+                &[("20251", "HK1 2025-2026"), ("20252", "HK2 2025-2026")],
+            ),
+            dropdown(
+                "cboTuan",
+                &[
+                    ("5", "Tuần 5 (01/09/2025 - 07/09/2025)"),
+                    ("6", "Tuần 6 (08/09/2025 - 14/09/2025)"),
+                ],
+            ),
+        ]
+    }
+
+    fn picked(name: &str, value: &str) -> Option<(String, String)> {
+        Some((name.to_string(), value.to_string()))
+    }
+
+    #[test]
+    fn week_option_finds_the_range_holding_the_date() {
+        let dropdowns = pickers();
+        assert_eq!(
+            week_option(&dropdowns, date("07/09/2025")),
+            picked("cboTuan", "5")
+        );
+        assert_eq!(
+            week_option(&dropdowns, date("08/09/2025")),
+            picked("cboTuan", "6")
+        );
+        assert_eq!(week_option(&dropdowns, date("15/09/2025")), None);
+    }
+
+    #[test]
+    fn week_option_reads_a_single_start_date() {
+        let dropdowns = [dropdown(
+            "cboTuan",
+            &[("01/09/2025", "Tuần 5"), ("08/09/2025", "Tuần 6")],
+        )];
+        assert_eq!(
+            week_option(&dropdowns, date("14/09/2025")),
+            picked("cboTuan", "08/09/2025")
+        );
+        assert_eq!(week_option(&dropdowns, date("31/08/2025")), None);
+    }
+
+    #[test]
+    fn semester_option_matches_value_or_text() {
+        let dropdowns = pickers();
+        assert_eq!(
+            semester_option(&dropdowns, "20252"),
+            picked("cboHocKy", "20252")
+        );
+        assert_eq!(
+            semester_option(&dropdowns, " hk1 2025 "),
+            picked("cboHocKy", "20251")
+        );
+        assert_eq!(semester_option(&dropdowns, "HK3"), None);
+    }
+
+    #[test]
+    fn semester_option_skips_week_pickers() {
+        let mut dropdowns = pickers();
+        dropdowns.reverse();
+        assert_eq!(semester_option(&dropdowns, "Tuần 5"), None);
+        assert_eq!(
+            semester_option(&dropdowns, "HK2"),
+            picked("cboHocKy", "20252")
+        );
+    }
+}
@@ -1,11 +1,15 @@
+use crate::error::Error;
 use dotenv::dotenv;
 use std::env;
 
-// #[derive(Parser)]
 #[derive(Debug)]
 pub struct UserConfig {
+    /// Prefix of the variables this was read from, "UDA_" or
+    /// "UDA_PROFILE_<NAME>_".
+    prefix: String,
     username: Option<String>,
     password: Option<String>,
+    base_url: Option<String>,
 }
 
 impl UserConfig {
@@ -18,6 +22,7 @@ impl UserConfig {
     pub fn from_env() -> Self {
         dotenv().ok();
         Self {
+            prefix: "UDA_".to_string(),
             username: env::var("UDA_USERNAME").ok().or_else(|| {
                 eprintln!("Warning: UDA_USERNAME not set, using None");
                 None
@@ -26,15 +31,63 @@ impl UserConfig {
                 eprintln!("Warning: UDA_PASSWORD not set, using None");
                 None
             }),
+            base_url: env::var("UDA_BASE_URL")
+                .ok()
+                .filter(|u| !u.trim().is_empty()),
         }
     }
 
-    pub fn validate(&self) -> Result<(), &'static str> {
+    /// Reads the profile `name` from `UDA_PROFILE_<NAME>_USERNAME` and
+    /// `UDA_PROFILE_<NAME>_PASSWORD`, with `<NAME>` upper-cased and anything
+    /// but letters and digits replaced by `_`. `UDA_PROFILE_<NAME>_BASE_URL`
+    /// falls back to `UDA_BASE_URL`. The profile "default" is
+    /// [`UserConfig::from_env`].
+    ///
+    /// # Examples
+    /// ```no_run
+    /// // UDA_PROFILE_AN_USERNAME=... and UDA_PROFILE_AN_PASSWORD=... in .env
+    /// let an = request::UserConfig::from_profile("an");
+    /// assert!(an.validate().is_ok());
+    /// ```
+    pub fn from_profile(name: &str) -> Self {
+        if name.eq_ignore_ascii_case("default") {
+            return Self::from_env();
+        }
+        dotenv().ok();
+        let id: String = name
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() {
+                    c.to_ascii_uppercase()
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        let prefix = format!("UDA_PROFILE_{}_", id);
+        let var = |key: &str| env::var(format!("{}{}", prefix, key)).ok();
+        Self {
+            username: var("USERNAME"),
+            password: var("PASSWORD"),
+            base_url: var("BASE_URL")
+                .or_else(|| env::var("UDA_BASE_URL").ok())
+                .filter(|u| !u.trim().is_empty()),
+            prefix,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), Error> {
         if self.username.is_none() {
-            return Err("Username not set");
+            return Err(Error::config(
+                &format!("{}USERNAME", self.prefix),
+                "not set",
+            ));
         }
         if self.password.is_none() {
-            return Err("Password not set");
+            return Err(Error::config(
+                &format!("{}PASSWORD", self.prefix),
+                "not set",
+            ));
         }
         Ok(())
     }
@@ -45,4 +98,16 @@ impl UserConfig {
     pub fn get_password(&self) -> Option<&String> {
         self.password.as_ref()
     }
+    /// Portal base URL from `UDA_BASE_URL`, if set.
+    pub fn get_base_url(&self) -> Option<&String> {
+        self.base_url.as_ref()
+    }
+    /// Name of the variable [`UserConfig::get_base_url`] came from, for
+    /// error messages.
+    pub fn base_url_var(&self) -> String {
+        match env::var(format!("{}BASE_URL", self.prefix)) {
+            Ok(_) => format!("{}BASE_URL", self.prefix),
+            Err(_) => "UDA_BASE_URL".to_string(),
+        }
+    }
 }
@@ -0,0 +1,87 @@
+use std::fmt;
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+
+/// A course title such as "Công nghệ IOT (3tc)", split into its display name
+/// and credit count.
+///
+/// `key` is an accent-free, lowercase slug of the name ("cong-nghe-iot") that
+/// is the same wherever the portal prints the course, so it can be used to
+/// join the timetable, upcoming schedule, exams and cancellation notices.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CourseTitle {
+    pub name: String,
+    pub credits: Option<u8>,
+    pub key: String,
+}
+
+impl CourseTitle {
+    /// Parses a title, normalising whitespace and Unicode (NFC). A trailing
+    /// "(3tc)", "(3 TC)" or "(3 tín chỉ)" is read as the credit count.
+    pub fn parse(s: &str) -> Self {
+        let normalized: String = s.nfc().collect();
+        let title = normalized.split_whitespace().collect::<Vec<_>>().join(" ");
+
+        let (name, credits) = match split_credits(&title) {
+            Some((name, credits)) => (name.trim_end().to_string(), Some(credits)),
+            None => (title, None),
+        };
+
+        Self {
+            key: course_key(&name),
+            name,
+            credits,
+        }
+    }
+
+    /// Same as [`CourseTitle::parse`], taking the credits from a separate
+    /// column (e.g. "Số TC") when the title doesn't carry them.
+    pub fn with_credits(s: &str, credits: Option<u8>) -> Self {
+        let mut title = Self::parse(s);
+        title.credits = title.credits.or(credits);
+        title
+    }
+
+    /// Whether this course is mentioned anywhere in `text`, comparing keys so
+    /// accents, case and punctuation don't matter.
+    pub fn is_mentioned_in(&self, text: &str) -> bool {
+        !self.key.is_empty()
+            && format!("-{}-", course_key(text)).contains(&format!("-{}-", self.key))
+    }
+}
+
+impl fmt::Display for CourseTitle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.credits {
+            Some(credits) => write!(f, "{} ({}tc)", self.name, credits),
+            None => f.write_str(&self.name),
+        }
+    }
+}
+
+fn split_credits(title: &str) -> Option<(&str, u8)> {
+    let open = title.strip_suffix(')')?.rfind('(')?;
+    let inner = title[open + 1..title.len() - 1].trim().to_lowercase();
+    let number = ["tín chỉ", "tc"]
+        .iter()
+        .find_map(|unit| inner.strip_suffix(unit))?;
+    Some((&title[..open], number.trim().parse().ok()?))
+}
+
+/// Accent-free, lowercase slug used to compare course names, e.g.
+/// "Đa văn hoá" -> "da-van-hoa".
+pub fn course_key(s: &str) -> String {
+    let plain: String = s
+        .nfd()
+        .filter(|c| !is_combining_mark(*c))
+        .map(|c| match c {
+            'đ' | 'Đ' => 'd',
+            c => c,
+        })
+        .flat_map(char::to_lowercase)
+        .collect();
+    plain
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
@@ -0,0 +1,131 @@
+use crate::login::LoginError;
+use crate::retry::short_reason;
+use chrono::NaiveDate;
+use std::fmt;
+use std::time::Duration;
+
+/// Anything that can go wrong between reading the configuration and having
+/// parsed the portal's pages.
+///
+/// Variants carry enough context (setting, page, selector, row) to tell
+/// what to fix without re-running with a debugger. The CLI maps each one to
+/// an exit code listed in the README.
+#[derive(Debug)]
+pub enum Error {
+    /// A setting is missing or invalid, e.g. `UDA_USERNAME` isn't set.
+    Config { setting: String, message: String },
+    /// Requesting a portal page (relative to `/sv/`) failed.
+    Network {
+        page: String,
+        source: reqwest::Error,
+    },
+    /// The global deadline passed before `page` could be loaded.
+    Deadline { page: String, deadline: Duration },
+    /// The portal refused the login.
+    Auth(LoginError),
+    /// An element the page always had is gone: the portal's layout changed,
+    /// or it answered with a different page than the one asked for.
+    Layout { page: String, selector: String },
+    /// A row of a table could not be read. `row` counts the table's `<tr>`s
+    /// from 1, header included.
+    Parse {
+        page: String,
+        selector: String,
+        row: usize,
+        message: String,
+    },
+    /// No week picker option covers the date.
+    NoSuchWeek(NaiveDate),
+    /// No semester picker option matches the id.
+    NoSuchSemester(String),
+}
+
+impl Error {
+    pub(crate) fn config(setting: &str, message: impl Into<String>) -> Self {
+        Error::Config {
+            setting: setting.to_string(),
+            message: message.into(),
+        }
+    }
+
+    pub(crate) fn network(page: &str, source: reqwest::Error) -> Self {
+        Error::Network {
+            page: page.to_string(),
+            source,
+        }
+    }
+
+    pub(crate) fn layout(page: &str, selector: &str) -> Self {
+        Error::Layout {
+            page: page.to_string(),
+            selector: selector.to_string(),
+        }
+    }
+
+    pub(crate) fn parse(
+        page: &str,
+        selector: &str,
+        row: usize,
+        message: impl Into<String>,
+    ) -> Self {
+        Error::Parse {
+            page: page.to_string(),
+            selector: selector.to_string(),
+            row,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Config { setting, message } => write!(f, "{}: {}", setting, message),
+            Error::Network { page, source } => {
+                write!(f, "could not load /sv/{}: {}", page, short_reason(source))
+            }
+            Error::Deadline { page, deadline } => write!(
+                f,
+                "gave up on /sv/{}: the {}s deadline passed",
+                page,
+                deadline.as_secs_f64()
+            ),
+            Error::Auth(e) => write!(f, "login failed: {}", e),
+            Error::Layout { page, selector } => write!(
+                f,
+                "/sv/{} has no `{}`, the portal layout may have changed",
+                page, selector
+            ),
+            Error::Parse {
+                page,
+                selector,
+                row,
+                message,
+            } => write!(f, "/sv/{}, `{}` row {}: {}", page, selector, row, message),
+            Error::NoSuchWeek(date) => write!(
+                f,
+                "the portal has no timetable week containing {}",
+                date.format("%d/%m/%Y")
+            ),
+            Error::NoSuchSemester(id) => {
+                write!(f, "the portal has no semester matching \"{}\"", id)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Network { source, .. } => Some(source),
+            Error::Auth(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<LoginError> for Error {
+    fn from(e: LoginError) -> Self {
+        Error::Auth(e)
+    }
+}
@@ -0,0 +1,195 @@
+use crate::bell::BellSchedule;
+use crate::course::CourseTitle;
+use crate::exam::ExamEntry;
+use crate::meeting::OnlineMeeting;
+use crate::notice::{session_status, CancellationNotice, SessionStatus};
+use crate::timetable::ClassSession;
+use crate::upcoming::UpcomingClass;
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime};
+
+/// Whether an [`Event`] is a class meeting or an exam.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    Class,
+    Exam,
+}
+
+/// A dated class meeting or exam, in local (UTC+7) time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    pub kind: EventKind,
+    pub starts_at: NaiveDateTime,
+    /// `None` for exams, whose end the portal doesn't give, and all-day
+    /// classes.
+    pub ends_at: Option<NaiveDateTime>,
+    /// Set for an exam whose start time couldn't be read, or a class whose
+    /// periods aren't in the bell schedule: it takes up its whole date, and
+    /// `starts_at` is midnight.
+    pub all_day: bool,
+    pub course: CourseTitle,
+    pub room: String,
+    pub online: Option<OnlineMeeting>,
+}
+
+impl Event {
+    fn class(class: &ClassSession, date: NaiveDate, bells: &BellSchedule) -> Self {
+        let span = bells.resolve(class.session, &class.periods);
+        Self {
+            kind: EventKind::Class,
+            starts_at: date.and_time(span.map_or(NaiveTime::MIN, |s| s.start)),
+            ends_at: span.map(|s| date.and_time(s.end)),
+            all_day: span.is_none(),
+            course: class.course.clone(),
+            room: class.room.clone(),
+            online: class.online.clone(),
+        }
+    }
+
+    fn exam(exam: &ExamEntry) -> Self {
+        Self {
+            kind: EventKind::Exam,
+            starts_at: exam.starts_at(),
+            ends_at: None,
+            all_day: exam.start_time.is_none(),
+            course: exam.course.clone(),
+            room: exam.room.clone(),
+            online: None,
+        }
+    }
+
+    /// Whether the event is still to come at `now`. An all-day event is
+    /// until its date is over.
+    pub fn is_upcoming(&self, now: NaiveDateTime) -> bool {
+        if self.all_day {
+            self.starts_at.date() >= now.date()
+        } else {
+            self.starts_at > now
+        }
+    }
+}
+
+/// The first class or exam starting after `after`.
+///
+/// The official timetable is taken to repeat weekly for the next 7 days, and
+/// upcoming-schedule classes count from their start date. Meetings that a
+/// notice cancels or moves are skipped. Exams without a start time take up
+/// their whole date, so they come first that day and stay next until it is
+/// over. So do classes whose periods aren't in `bells` (evening classes by
+/// default), but after that day's classes and exams with a time, as they
+/// are most likely late in the day.
+pub fn next_event(
+    sessions: &[ClassSession],
+    upcoming: &[UpcomingClass],
+    exams: &[ExamEntry],
+    notices: &[CancellationNotice],
+    bells: &BellSchedule,
+    after: NaiveDateTime,
+) -> Option<Event> {
+    let first_day = after.date();
+    let timetable = first_day.iter_days().take(8).flat_map(|date| {
+        sessions
+            .iter()
+            .filter(move |class| class.weekday == date.weekday())
+            .filter(move |class| session_status(class, date, notices) == SessionStatus::Unaffected)
+            .map(move |class| Event::class(class, date, bells))
+    });
+
+    // Today's meeting may already have started, so look at the one after too
+    let starting = upcoming.iter().flat_map(|class| {
+        let session = class.to_session();
+        class
+            .occurrences()
+            .skip_while(|date| *date < first_day)
+            .take(2)
+            .filter(|date| session_status(&session, *date, notices) == SessionStatus::Unaffected)
+            .map(|date| Event::class(&session, date, bells))
+            .collect::<Vec<_>>()
+    });
+
+    timetable
+        .chain(starting)
+        .chain(exams.iter().map(Event::exam))
+        .filter(|event| event.is_upcoming(after))
+        .min_by_key(|event| {
+            let untimed_class = event.all_day && event.kind == EventKind::Class;
+            (event.starts_at.date(), untimed_class, event.starts_at)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exam::parse_clock_time;
+    use crate::group::ClassGroup;
+    use crate::timetable::{PeriodRange, Session};
+
+    fn exam(date: &str, time_text: &str) -> ExamEntry {
+        ExamEntry {
+            semester: "HK1 2025-2026".to_string(),
+            course: CourseTitle::parse("Công nghệ IOT (3tc)"),
+            credits: Some(3),
+            date: NaiveDate::parse_from_str(date, "%d/%m/%Y").unwrap(),
+            slot: "1".to_string(),
+            start_time: parse_clock_time(time_text),
+            time_text: time_text.to_string(),
+            room: "906".to_string(),
+            format: "Tự luận".to_string(),
+        }
+    }
+
+    fn at(date: &str, time: &str) -> NaiveDateTime {
+        NaiveDate::parse_from_str(date, "%d/%m/%Y")
+            .unwrap()
+            .and_time(NaiveTime::parse_from_str(time, "%H:%M").unwrap())
+    }
+
+    fn next(exams: &[ExamEntry], after: NaiveDateTime) -> Option<Event> {
+        next_event(&[], &[], exams, &[], &BellSchedule::default(), after)
+    }
+
+    #[test]
+    fn exam_without_time_is_next_all_day() {
+        let exams = [exam("30/12/2025", "Chưa xếp")];
+        let event = next(&exams, at("30/12/2025", "15:00")).unwrap();
+        assert!(event.all_day);
+        assert_eq!(event.starts_at, at("30/12/2025", "00:00"));
+        assert_eq!(next(&exams, at("31/12/2025", "00:00")), None);
+    }
+
+    fn class(session: &str, periods: &str) -> ClassSession {
+        ClassSession {
+            weekday: chrono::Weekday::Tue,
+            session: Session::parse(session).unwrap(),
+            periods: PeriodRange::parse(periods).unwrap(),
+            room: "707".to_string(),
+            course: CourseTitle::parse("Lập trình Web 2 (3tc)"),
+            lecturer: "ĐH. D".to_string(),
+            class_group: ClassGroup::parse(""),
+            online: None,
+        }
+    }
+
+    #[test]
+    fn class_without_bell_times_is_next_all_day_after_timed_ones() {
+        let sessions = [class("Tối", "1-2"), class("Sáng", "1-3")];
+        let bells = BellSchedule::default();
+        // 30/12/2025 is a Tuesday
+        let event =
+            next_event(&sessions, &[], &[], &[], &bells, at("30/12/2025", "06:00")).unwrap();
+        assert!(!event.all_day);
+        assert_eq!(event.starts_at, at("30/12/2025", "07:00"));
+
+        let event =
+            next_event(&sessions, &[], &[], &[], &bells, at("30/12/2025", "12:00")).unwrap();
+        assert!(event.all_day);
+        assert_eq!(event.starts_at, at("30/12/2025", "00:00"));
+        assert_eq!(event.ends_at, None);
+    }
+
+    #[test]
+    fn exam_with_time_is_next_until_it_starts() {
+        let exams = [exam("30/12/2025", "7g30")];
+        assert!(!next(&exams, at("30/12/2025", "07:00")).unwrap().all_day);
+        assert_eq!(next(&exams, at("30/12/2025", "07:30")), None);
+    }
+}
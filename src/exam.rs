@@ -0,0 +1,139 @@
+use crate::course::CourseTitle;
+use crate::error::Error;
+use crate::timetable::{find_grid, read_rows};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use scraper::{Html, Selector};
+
+/// One row of the exam schedule (`/sv/lichthi`, `#MainContent_GV2`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExamEntry {
+    pub semester: String,
+    pub course: CourseTitle,
+    /// "Số TC".
+    pub credits: Option<u8>,
+    pub date: NaiveDate,
+    /// "Xuất" (exam slot) as printed by the portal.
+    pub slot: String,
+    /// Start time parsed from "Thời gian thi", if it could be read.
+    pub start_time: Option<NaiveTime>,
+    /// Raw "Thời gian thi" text.
+    pub time_text: String,
+    pub room: String,
+    /// "Hình thức" (written, multiple choice, ...).
+    pub format: String,
+}
+
+impl ExamEntry {
+    /// Local date and time the exam starts, falling back to midnight when the
+    /// start time couldn't be parsed (the exam then counts as all-day, see
+    /// [`Event::all_day`](crate::Event::all_day)).
+    pub fn starts_at(&self) -> NaiveDateTime {
+        self.date
+            .and_time(self.start_time.unwrap_or(NaiveTime::MIN))
+    }
+}
+
+/// Parses a portal clock time such as "7g30", "7h", "07:30" or "13g30 - 15g00"
+/// (only the first time is read).
+pub fn parse_clock_time(s: &str) -> Option<NaiveTime> {
+    let start = s.trim().split(['-', '–']).next()?.trim().to_lowercase();
+    let (hour, minute) = match start.split_once(['g', 'h', ':']) {
+        Some((h, m)) => {
+            let m = m.trim().trim_end_matches('\'');
+            (h.trim(), if m.is_empty() { "0" } else { m })
+        }
+        None => (start.as_str(), "0"),
+    };
+    NaiveTime::from_hms_opt(hour.parse().ok()?, minute.parse().ok()?, 0)
+}
+
+/// Parses the exam schedule page into [`ExamEntry`]s.
+///
+/// A page without the table is an [`Error::Layout`]. Rows whose "Ngày thi"
+/// has no digits (not scheduled yet) are left out, as are single-cell rows.
+/// So are rows with too few cells or a date that isn't dd/mm/yyyy; their
+/// errors go in `skipped`, and are an [`Error::Parse`] only if no row could
+/// be read.
+///
+/// # Arguments
+///
+/// * `html` - A reference to a parsed HTML document from the `scraper` crate.
+/// * `tr` - A `Selector` for the table rows (`<tr>`) that contain exam data.
+/// * `td` - A `Selector` for the table data cells (`<td>`) within each selected row.
+/// * `skipped` - Collects the errors of the rows that were left out.
+pub fn parse_exams(
+    html: &Html,
+    tr: &Selector,
+    td: &Selector,
+    skipped: &mut Vec<Error>,
+) -> Result<Vec<ExamEntry>, Error> {
+    const TABLE: &str = "#MainContent_GV2";
+    let Some(r) = find_grid(html, "lichthi", TABLE)? else {
+        return Ok(Vec::new());
+    };
+
+    read_rows(
+        r.select(tr).enumerate().map(|(i, row)| {
+            let row_data: Vec<_> = row
+                .select(td)
+                .map(|cell| cell.text().collect::<String>().trim().to_string())
+                .collect();
+            if row_data.len() <= 1 {
+                return Ok(None);
+            }
+            let invalid = |message: String| Error::parse("lichthi", TABLE, i + 1, message);
+            if row_data.len() < 8 {
+                return Err(invalid(format!(
+                    "expected 8 cells, found {}",
+                    row_data.len()
+                )));
+            }
+            if !row_data[3].chars().any(|c| c.is_ascii_digit()) {
+                return Ok(None);
+            }
+            let date = NaiveDate::parse_from_str(&row_data[3], "%d/%m/%Y")
+                .map_err(|_| invalid(format!("unreadable exam date `{}`", row_data[3])))?;
+
+            let credits = row_data[2].parse().ok();
+            Ok(Some(ExamEntry {
+                semester: row_data[0].clone(),
+                course: CourseTitle::with_credits(&row_data[1], credits),
+                credits,
+                date,
+                slot: row_data[4].clone(),
+                start_time: parse_clock_time(&row_data[5]),
+                time_text: row_data[5].clone(),
+                room: row_data[6].clone(),
+                format: row_data[7].clone(),
+            }))
+        }),
+        skipped,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(h: u32, m: u32) -> Option<NaiveTime> {
+        NaiveTime::from_hms_opt(h, m, 0)
+    }
+
+    #[test]
+    fn parse_clock_time_reads_portal_forms() {
+        assert_eq!(parse_clock_time("7g30"), time(7, 30));
+        assert_eq!(parse_clock_time("7h"), time(7, 0));
+        assert_eq!(parse_clock_time("07:30"), time(7, 30));
+        assert_eq!(parse_clock_time("9g30'"), time(9, 30));
+        assert_eq!(parse_clock_time(" 13g30 - 15g00 "), time(13, 30));
+        assert_eq!(parse_clock_time("13h30–15h00"), time(13, 30));
+        assert_eq!(parse_clock_time("15"), time(15, 0));
+    }
+
+    #[test]
+    fn parse_clock_time_rejects_missing_times() {
+        assert_eq!(parse_clock_time(""), None);
+        assert_eq!(parse_clock_time("Chưa xếp"), None);
+        assert_eq!(parse_clock_time("25g00"), None);
+    }
+}
@@ -0,0 +1,161 @@
+use prettytable::Table;
+
+/// Writes a table built with [`table_header!`](crate::table_header) as CSV.
+/// Cells containing commas, quotes or line breaks are quoted.
+pub fn table_to_csv(table: &Table) -> String {
+    let bytes = table
+        .to_csv(Vec::new())
+        .expect("writing CSV to memory doesn't fail")
+        .into_inner()
+        .expect("writing CSV to memory doesn't fail");
+    String::from_utf8(bytes).expect("table cells are UTF-8")
+}
+
+/// Writes a table built with [`table_header!`](crate::table_header) as a
+/// GitHub-flavoured Markdown table, its first row being the header.
+pub fn table_to_markdown(table: &Table) -> String {
+    let mut rows = table.row_iter().map(|row| {
+        let cells: Vec<String> = row
+            .iter()
+            .map(|cell| escape_markdown(&cell.get_content()))
+            .collect();
+        format!("| {} |\n", cells.join(" | "))
+    });
+    let Some(header) = rows.next() else {
+        return String::new();
+    };
+    let columns = table.get_row(0).map_or(0, |row| row.len());
+    let rule = format!("|{}\n", " --- |".repeat(columns));
+    header + &rule + &rows.collect::<String>()
+}
+
+/// Writes a table built with [`table_header!`](crate::table_header) as an
+/// HTML `<table>`, its first row being the header.
+pub fn table_to_html(table: &Table) -> String {
+    let mut out = String::from("<table>\n");
+    for (i, row) in table.row_iter().enumerate() {
+        let tag = if i == 0 { "th" } else { "td" };
+        out.push_str("<tr>");
+        for cell in row.iter() {
+            out.push_str(&format!(
+                "<{tag}>{}</{tag}>",
+                escape_html(&cell.get_content()).replace('\n', "<br>")
+            ));
+        }
+        out.push_str("</tr>\n");
+    }
+    out.push_str("</table>\n");
+    out
+}
+
+/// Wraps `body` in a self-contained HTML page: UTF-8, no external styles or
+/// scripts, laid out to print on A4.
+pub fn html_document(title: &str, body: &str) -> String {
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="vi">
+<head>
+<meta charset="utf-8">
+<title>{}</title>
+<style>
+@page {{ size: A4 landscape; margin: 1cm; }}
+body {{ font-family: sans-serif; font-size: 11pt; color: #000; }}
+table {{ border-collapse: collapse; width: 100%; margin-bottom: 1.5em; page-break-inside: avoid; }}
+th, td {{ border: 1px solid #444; padding: 4px 6px; text-align: left; vertical-align: top; }}
+th {{ background: #e8e8e8; }}
+.error {{ color: #b00; }}
+</style>
+</head>
+<body>
+{}</body>
+</html>
+"#,
+        escape_html(title),
+        body
+    )
+}
+
+/// Escapes text for HTML element content and attribute values.
+pub fn escape_html(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Escapes text for a Markdown table cell: characters with a meaning in
+/// Markdown get a backslash and line breaks become `<br>`.
+pub fn escape_markdown(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '\\' | '|' | '*' | '_' | '`' | '~' | '[' | ']' | '<' | '>' | '#' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '\n' => out.push_str("<br>"),
+            '\r' => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prettytable::{Cell, Row};
+
+    fn table(rows: &[&[&str]]) -> Table {
+        let mut table = Table::new();
+        for row in rows {
+            table.add_row(Row::new(row.iter().map(|text| Cell::new(text)).collect()));
+        }
+        table
+    }
+
+    #[test]
+    fn escape_markdown_keeps_cells_in_place() {
+        assert_eq!(escape_markdown("a | b"), r"a \| b");
+        assert_eq!(escape_markdown("*Tối* <1>"), r"\*Tối\* \<1\>");
+        assert_eq!(escape_markdown("Web 2\r\n(3tc)"), "Web 2<br>(3tc)");
+        assert_eq!(escape_markdown("\"R&D\""), "\"R&D\"");
+    }
+
+    #[test]
+    fn escape_html_escapes_markup_and_quotes() {
+        assert_eq!(
+            escape_html(r#"<a href="x">R&D 'IOT'</a>"#),
+            "&lt;a href=&quot;x&quot;&gt;R&amp;D &#39;IOT&#39;&lt;/a&gt;"
+        );
+        assert_eq!(escape_html("a | *b*\nc"), "a | *b*\nc");
+    }
+
+    #[test]
+    fn markdown_table_has_a_rule_after_the_header() {
+        let table = table(&[&["THỨ", "HỌC PHẦN"], &["3", "Web | *2*\nOnline"]]);
+        assert_eq!(
+            table_to_markdown(&table),
+            "| THỨ | HỌC PHẦN |\n| --- | --- |\n| 3 | Web \\| \\*2\\*<br>Online |\n"
+        );
+        assert_eq!(table_to_markdown(&Table::new()), "");
+    }
+
+    #[test]
+    fn html_table_escapes_cells_and_breaks_lines() {
+        let table = table(&[&["PHÒNG"], &["<b>707</b> & \"Online\"\nZoom"]]);
+        assert_eq!(
+            table_to_html(&table),
+            "<table>\n<tr><th>PHÒNG</th></tr>\n\
+             <tr><td>&lt;b&gt;707&lt;/b&gt; &amp; &quot;Online&quot;<br>Zoom</td></tr>\n</table>\n"
+        );
+    }
+}
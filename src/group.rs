@@ -0,0 +1,68 @@
+use std::fmt;
+
+/// The "Lớp học tập" column, e.g. "7203(ST22A,ST22B,GD22A)": a class-section
+/// code followed by the cohorts that attend it together.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct ClassGroup {
+    pub code: String,
+    pub cohorts: Vec<String>,
+}
+
+impl ClassGroup {
+    /// Parses "CODE(COHORT,COHORT,...)". Text without parentheses is taken as
+    /// the code alone; an empty cell gives an empty group.
+    pub fn parse(s: &str) -> Self {
+        let s = s.trim();
+        let Some((code, rest)) = s.split_once('(') else {
+            return Self {
+                code: s.to_string(),
+                cohorts: Vec::new(),
+            };
+        };
+        let cohorts = rest
+            .trim_end()
+            .trim_end_matches(')')
+            .split([',', ';'])
+            .map(|c| c.trim().to_uppercase())
+            .filter(|c| !c.is_empty())
+            .collect();
+        Self {
+            code: code.trim().to_string(),
+            cohorts,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.code.is_empty() && self.cohorts.is_empty()
+    }
+
+    /// Whether `cohort` (e.g. "ST22B") attends this class, ignoring case.
+    pub fn includes(&self, cohort: &str) -> bool {
+        self.cohorts
+            .iter()
+            .any(|c| c.eq_ignore_ascii_case(cohort.trim()))
+    }
+
+    /// Whether several cohorts share this class.
+    pub fn is_combined(&self) -> bool {
+        self.cohorts.len() > 1
+    }
+
+    /// The cohorts other than `cohort` that share this class.
+    pub fn shared_with<'a>(&'a self, cohort: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.cohorts
+            .iter()
+            .filter(move |c| !c.eq_ignore_ascii_case(cohort.trim()))
+            .map(String::as_str)
+    }
+}
+
+impl fmt::Display for ClassGroup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.code)?;
+        if !self.cohorts.is_empty() {
+            write!(f, "({})", self.cohorts.join(","))?;
+        }
+        Ok(())
+    }
+}
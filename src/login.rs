@@ -0,0 +1,199 @@
+use reqwest::Url;
+use scraper::{Html, Selector};
+use std::fmt;
+
+/// Why a login attempt didn't produce a usable session.
+///
+/// The portal answers `200 OK` to a failed login too, so these are read from
+/// the page it lands on (see [`check_login_response`]).
+#[derive(Debug)]
+pub enum LoginError {
+    /// The login form came back, with the portal's message if it showed one.
+    WrongCredentials(Option<String>),
+    AccountLocked(Option<String>),
+    /// The portal is showing a maintenance page.
+    Maintenance,
+    /// The account must change its password on the portal before use.
+    PasswordChangeRequired,
+}
+
+impl fmt::Display for LoginError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoginError::WrongCredentials(Some(msg)) => {
+                write!(f, "wrong username or password ({})", msg)
+            }
+            LoginError::WrongCredentials(None) => f.write_str("wrong username or password"),
+            LoginError::AccountLocked(Some(msg)) => write!(f, "account is locked ({})", msg),
+            LoginError::AccountLocked(None) => f.write_str("account is locked"),
+            LoginError::Maintenance => f.write_str("the portal is under maintenance"),
+            LoginError::PasswordChangeRequired => f.write_str(
+                "the portal requires a password change, log in to it in a browser first",
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LoginError {}
+
+fn contains_any(haystack: &str, needles: &[&str]) -> bool {
+    needles.iter().any(|n| haystack.contains(n))
+}
+
+const LOCKED: &[&str] = &["bị khóa", "bị khoá", "tạm khóa", "tạm khoá", "locked"];
+const PASSWORD_CHANGE: &[&str] = &["đổi mật khẩu", "doimatkhau", "changepassword"];
+const MAINTENANCE: &[&str] = &["bảo trì", "baotri", "maintenance"];
+
+/// Decides whether the page the login POST ended on (after redirects) is a
+/// logged-in page.
+///
+/// A failure is recognised by the final URL (still on `svlogin`, or on a
+/// password-change or maintenance page) or by the login form's password
+/// field still being there. The message label next to the form tells wrong
+/// credentials apart from a locked account.
+pub fn check_login_response(final_url: &Url, body: &str) -> Result<(), LoginError> {
+    let path = final_url.path().to_lowercase();
+    let html = Html::parse_document(body);
+    let page_text = html
+        .root_element()
+        .text()
+        .collect::<String>()
+        .to_lowercase();
+
+    if contains_any(&path, PASSWORD_CHANGE) {
+        return Err(LoginError::PasswordChangeRequired);
+    }
+    if contains_any(&path, MAINTENANCE) {
+        return Err(LoginError::Maintenance);
+    }
+
+    if !is_login_form(final_url, &html) {
+        return Ok(());
+    }
+
+    if contains_any(&page_text, MAINTENANCE) {
+        return Err(LoginError::Maintenance);
+    }
+
+    let message = error_message(&html);
+    let lower = message.as_deref().unwrap_or("").to_lowercase();
+    if contains_any(&lower, LOCKED) {
+        Err(LoginError::AccountLocked(message))
+    } else if contains_any(&lower, PASSWORD_CHANGE) {
+        Err(LoginError::PasswordChangeRequired)
+    } else {
+        Err(LoginError::WrongCredentials(message))
+    }
+}
+
+/// Whether a page that was asked for came back as the login page instead,
+/// i.e. the session has expired.
+pub(crate) fn is_login_page(final_url: &Url, body: &str) -> bool {
+    is_login_form(final_url, &Html::parse_document(body))
+}
+
+/// On `svlogin`, or showing the login form's password field.
+fn is_login_form(final_url: &Url, html: &Html) -> bool {
+    let password_field = Selector::parse("input[type=password]").unwrap();
+    final_url.path().to_lowercase().contains("svlogin")
+        || html.select(&password_field).next().is_some()
+}
+
+/// Text of the first non-empty message label on the login page.
+fn error_message(html: &Html) -> Option<String> {
+    let labels = Selector::parse(
+        "span[id*=lbl], span[id*=Lbl], span[id*=Label], .alert, .text-danger, .error, font[color=red]",
+    )
+    .unwrap();
+    html.select(&labels)
+        .map(|e| {
+            e.text()
+                .collect::<String>()
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .find(|t| !t.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(path: &str, body: &str) -> Result<(), LoginError> {
+        let url = Url::parse("https://my.uda.edu.vn")
+            .unwrap()
+            .join(path)
+            .unwrap();
+        check_login_response(&url, body)
+    }
+
+    fn login_form(message: &str) -> String {
+        format!(
+            r#"<form><input type="text" name="txtUser"><input type="password" name="txtPass">
+<span id="MainContent_lblThongBao">{}</span></form>"#,
+            message
+        )
+    }
+
+    #[test]
+    fn wrong_password_keeps_the_form_and_its_message() {
+        let result = check(
+            "/sv/svlogin",
+            &login_form("Sai tên đăng nhập hoặc mật khẩu"),
+        );
+        assert!(matches!(
+            result,
+            Err(LoginError::WrongCredentials(Some(m))) if m == "Sai tên đăng nhập hoặc mật khẩu"
+        ));
+        assert!(matches!(
+            check("/sv/svlogin", &login_form("")),
+            Err(LoginError::WrongCredentials(None))
+        ));
+    }
+
+    #[test]
+    fn locked_account_is_told_apart_by_its_message() {
+        let result = check("/sv/svlogin", &login_form("Tài khoản của bạn đã bị khóa"));
+        assert!(matches!(result, Err(LoginError::AccountLocked(Some(_)))));
+    }
+
+    #[test]
+    fn maintenance_page_or_notice() {
+        assert!(matches!(
+            check("/BaoTri.aspx", "<p>Hệ thống đang bảo trì</p>"),
+            Err(LoginError::Maintenance)
+        ));
+        assert!(matches!(
+            check(
+                "/sv/svlogin",
+                &format!("<p>Hệ thống đang bảo trì</p>{}", login_form(""))
+            ),
+            Err(LoginError::Maintenance)
+        ));
+    }
+
+    #[test]
+    fn password_change_by_page_or_message() {
+        assert!(matches!(
+            check(
+                "/sv/DoiMatKhau.aspx",
+                "<form><input type=\"password\"></form>"
+            ),
+            Err(LoginError::PasswordChangeRequired)
+        ));
+        assert!(matches!(
+            check(
+                "/sv/svlogin",
+                &login_form("Bạn phải đổi mật khẩu trước khi sử dụng")
+            ),
+            Err(LoginError::PasswordChangeRequired)
+        ));
+    }
+
+    #[test]
+    fn redirect_away_from_login_is_success() {
+        let body = r#"<div id="MainContent_home">Chào mừng</div>"#;
+        assert!(check("/sv/Default.aspx", body).is_ok());
+    }
+}
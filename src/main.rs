@@ -1,20 +1,302 @@
+use chrono::{Datelike, NaiveDate, NaiveDateTime};
+use clap::{Parser, Subcommand, ValueEnum};
 use colored::Colorize;
-use indicatif::ProgressBar;
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use prettytable::Table;
 use request::{
-    cancellation_notice, exam_schedule, extract_upcoming_schedule, timetable_table, UserConfig,
+    cancellation_notice, default_cookie_path, escape_html, escape_markdown, exam_schedule,
+    html_document, next_event, next_event_line, now, parse_base_url, parse_date, parse_seconds,
+    table_to_csv, table_to_html, table_to_markdown, timetable_table, today, today_summary,
+    today_table, upcoming_schedule_table, BellSchedule, CancellationNotice, ClassSession, Error,
+    Event, ExamEntry, ExamPage, LoginError, ProfileReport, Report, RetryPolicy, TimetablePage,
+    TimetableQuery, UdaClient, UdaClientBuilder, UpcomingClass, UserConfig, DEFAULT_BASE_URL,
 };
-use reqwest::{cookie::Jar, Client};
-use scraper::{Html, Selector};
-use std::collections::HashMap;
-use std::sync::Arc;
+use reqwest::Url;
 use std::time::Duration;
 
 use dotenv::dotenv;
+use futures::future::join_all;
 use std::env;
 
 mod quote;
 use crate::quote::get_quote;
 
+/// Fetch and display the UDA timetable, exam schedule and notices.
+///
+/// Without a command, everything is shown.
+#[derive(Parser)]
+#[command(version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Show classes, exams and upcoming meetings as of this date instead of today
+    /// (YYYY-MM-DD, dd/mm/yyyy, tomorrow, +3d, next mon, ...)
+    #[arg(long, global = true, value_parser = parse_cli_date)]
+    date: Option<NaiveDate>,
+
+    /// Show the timetable of the week containing this date (same forms as --date)
+    #[arg(long, global = true, value_parser = parse_cli_date)]
+    week: Option<NaiveDate>,
+
+    /// Show the timetable of this semester, by id or name as listed on the portal
+    #[arg(long, global = true)]
+    semester: Option<String>,
+
+    /// Profiles to show, comma-separated (see UDA_PROFILE_<NAME>_USERNAME in the README)
+    #[arg(long, global = true, value_delimiter = ',')]
+    profile: Vec<String>,
+
+    /// Portal to use instead of https://my.uda.edu.vn (overrides UDA_BASE_URL)
+    #[arg(long, global = true, value_parser = parse_base_url)]
+    base_url: Option<Url>,
+
+    /// Seconds a single request may take (overrides UDA_TIMEOUT, default 20)
+    #[arg(long, global = true, value_parser = parse_seconds)]
+    timeout: Option<Duration>,
+
+    /// Times a failed page load is retried (overrides UDA_RETRIES, default 3)
+    #[arg(long, global = true)]
+    retries: Option<u32>,
+
+    /// Seconds the whole run may take (overrides UDA_DEADLINE, default none)
+    #[arg(long, global = true, value_parser = parse_seconds)]
+    deadline: Option<Duration>,
+
+    /// How to print the sections
+    #[arg(long, global = true, value_enum, default_value_t = Format::Table)]
+    format: Format,
+}
+
+/// Output formats for `--format`.
+#[derive(ValueEnum, Clone, Copy, PartialEq, Eq, Default)]
+enum Format {
+    /// Coloured tables for the terminal
+    #[default]
+    Table,
+    /// One JSON document, described by schema/output.schema.json
+    Json,
+    /// One CSV table per section, separated by a blank line
+    Csv,
+    /// Markdown tables under a heading per section
+    Markdown,
+    /// A self-contained HTML page, ready to print
+    Html,
+}
+
+/// Shows a single section, fetching only the page it comes from.
+#[derive(Subcommand, Clone, Copy, PartialEq, Eq)]
+enum Command {
+    /// Today's (or --date's) classes, with cancellations applied
+    Today,
+    /// The official timetable of the week (this week, or the one of --week)
+    Week,
+    /// Courses starting in the coming weeks
+    Upcoming,
+    /// Exams from today (or --date) on
+    Exams,
+    /// Cancellation and make-up notices
+    Notices,
+    /// The next class or exam (after --date, if given) and how long until it starts
+    Next {
+        /// Keep the countdown updating until interrupted
+        #[arg(long)]
+        watch: bool,
+    },
+}
+
+/// Whether `section` is shown when running `command` (everything when no
+/// command is given).
+fn shows(command: Option<Command>, section: Command) -> bool {
+    command.is_none_or(|c| c == section)
+}
+
+fn parse_cli_date(s: &str) -> Result<NaiveDate, String> {
+    parse_date(s, today())
+}
+
+/// Exit codes, documented in the README.
+fn exit_code(e: &Error) -> i32 {
+    match e {
+        Error::Config { .. } => 1,
+        Error::Network { .. } => 3,
+        Error::Layout { .. } => 4,
+        Error::Parse { .. } => 5,
+        Error::Deadline { .. } => 6,
+        Error::NoSuchWeek(_) | Error::NoSuchSemester(_) => 8,
+        Error::Auth(LoginError::WrongCredentials(_)) => 10,
+        Error::Auth(LoginError::AccountLocked(_)) => 11,
+        Error::Auth(LoginError::Maintenance) => 12,
+        Error::Auth(LoginError::PasswordChangeRequired) => 13,
+    }
+}
+
+/// Exit code when some sections were shown and others failed.
+const PARTIAL: i32 = 7;
+
+/// Which sections could be shown and which failed, to pick the exit code.
+#[derive(Default)]
+struct Outcome {
+    shown: usize,
+    failed: Vec<i32>,
+}
+
+impl Outcome {
+    fn shown(&mut self) {
+        self.shown += 1;
+    }
+
+    /// Records a section that failed with exit code `code`.
+    fn failed(&mut self, code: i32) {
+        self.failed.push(code);
+    }
+
+    /// 0 if every section was shown, [`PARTIAL`] if only some were, and the
+    /// first failure's code if none were.
+    fn exit_code(&self) -> i32 {
+        match (self.shown, self.failed.first()) {
+            (_, None) => 0,
+            (0, Some(&code)) => code,
+            _ => PARTIAL,
+        }
+    }
+}
+
+/// The formats that are printed section by section: every one but JSON,
+/// which is written from a [`Report`] instead.
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+enum Layout {
+    #[default]
+    Table,
+    Csv,
+    Markdown,
+    Html,
+}
+
+impl Layout {
+    /// `None` for [`Format::Json`].
+    fn of(format: Format) -> Option<Self> {
+        match format {
+            Format::Table => Some(Layout::Table),
+            Format::Json => None,
+            Format::Csv => Some(Layout::Csv),
+            Format::Markdown => Some(Layout::Markdown),
+            Format::Html => Some(Layout::Html),
+        }
+    }
+}
+
+/// Prints the sections of the output in the chosen layout, keeping track of
+/// which could be shown to pick the exit code.
+#[derive(Default)]
+struct Sections {
+    outcome: Outcome,
+    layout: Layout,
+    /// Output of the layouts other than [`Layout::Table`], printed as one
+    /// document by [`Sections::finish`].
+    document: String,
+}
+
+impl Sections {
+    fn new(layout: Layout) -> Self {
+        Self {
+            layout,
+            ..Self::default()
+        }
+    }
+
+    /// Prints `table` (built with `table_header!`) under `title`, or `empty`
+    /// if it has no rows.
+    fn table(&mut self, title: &str, table: &Table, empty: &str) {
+        let has_rows = table.len() > 1;
+        match self.layout {
+            Layout::Table if has_rows => {
+                println!("{}", title);
+                table.printstd();
+            }
+            Layout::Table => println!("{}", empty),
+            // Keep the header so an empty section still reads as a table
+            Layout::Csv => {
+                if !self.document.is_empty() {
+                    self.document.push('\n');
+                }
+                self.document.push_str(&table_to_csv(table));
+            }
+            Layout::Markdown => {
+                self.document += &format!("## {}\n\n", escape_markdown(title));
+                if has_rows {
+                    self.document += &table_to_markdown(table);
+                } else {
+                    self.document += &format!("{}\n", escape_markdown(empty));
+                }
+                self.document.push('\n');
+            }
+            Layout::Html => {
+                self.document += &format!("<h2>{}</h2>\n", escape_html(title));
+                if has_rows {
+                    self.document += &table_to_html(table);
+                } else {
+                    self.document += &format!("<p>{}</p>\n", escape_html(empty));
+                }
+            }
+        }
+    }
+
+    /// Heading of one profile's sections.
+    fn heading(&mut self, text: &str) {
+        match self.layout {
+            Layout::Table => println!("{}", format!("══ {} ══", text).bold()),
+            Layout::Csv => {}
+            Layout::Markdown => self.document += &format!("# {}\n\n", escape_markdown(text)),
+            Layout::Html => self.document += &format!("<h1>{}</h1>\n", escape_html(text)),
+        }
+    }
+
+    /// Prints the document the sections were collected into, if any.
+    fn finish(&self) {
+        match self.layout {
+            Layout::Html => print!("{}", html_document("Thời khóa biểu MyUDA", &self.document)),
+            _ => print!("{}", self.document),
+        }
+    }
+
+    fn shown(&mut self) {
+        self.outcome.shown();
+    }
+
+    /// Prints why the section `title` is missing.
+    fn failed(&mut self, title: &str, e: &Error) {
+        self.failed_with(title, e, exit_code(e));
+    }
+
+    fn failed_with(&mut self, title: &str, e: &dyn std::fmt::Display, code: i32) {
+        match self.layout {
+            Layout::Table => println!("{} {}", format!("{}:", title).red().bold(), e),
+            // The error isn't part of the data, so it doesn't go in the CSV
+            Layout::Csv => eprintln!("{}: {}", title, e),
+            Layout::Markdown => {
+                self.document += &format!(
+                    "**{}:** {}\n\n",
+                    escape_markdown(title),
+                    escape_markdown(&e.to_string())
+                )
+            }
+            Layout::Html => {
+                self.document += &format!(
+                    "<p class=\"error\"><strong>{}:</strong> {}</p>\n",
+                    escape_html(title),
+                    escape_html(&e.to_string())
+                )
+            }
+        }
+        self.outcome.failed(code);
+    }
+
+    fn exit_code(&self) -> i32 {
+        self.outcome.exit_code()
+    }
+}
+
 /*=======================================================================================================+
  |  ███╗   ███╗ █████╗ ██╗███╗   ██╗    ██████╗ ██████╗  ██████╗  ██████╗ ██████╗  █████╗ ███╗   ███╗    |
  |  ████╗ ████║██╔══██╗██║████╗  ██║    ██╔══██╗██╔══██╗██╔═══██╗██╔════╝ ██╔══██╗██╔══██╗████╗ ████║    |
@@ -25,158 +307,714 @@ use crate::quote::get_quote;
  *=======================================================================================================+
 */
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    dotenv().ok();
-    let daily_quote_api = env::var("DAILY_QUOTE_API")
-        .ok()
-        .or_else(|| {
-            eprintln!("Warning: DAILY_QUOTE_API not set, using None");
-            None
-        })
-        .unwrap();
+async fn main() {
+    let cli = Cli::parse();
+    let bar = ProgressBar::new_spinner();
 
-    // Load environment variables from .env file
-    let user_config = UserConfig::from_env();
+    match run(cli, &bar).await {
+        Ok(0) => {}
+        Ok(code) => std::process::exit(code),
+        Err(e) => {
+            bar.finish_and_clear();
+            eprintln!("Error: {}", e);
+            std::process::exit(exit_code(&e));
+        }
+    }
+}
 
-    if let Err(e) = user_config.validate() {
-        eprintln!("Configuration error: {}", e);
-        std::process::exit(1);
+/// Fetches and prints everything, returning the exit code: 0, or
+/// [`PARTIAL`] if some sections couldn't be shown. Errors that leave nothing
+/// to show (configuration, or every login failing) are returned instead.
+async fn run(cli: Cli, bar: &ProgressBar) -> Result<i32, Error> {
+    let command = cli.command;
+    let date = cli.date.unwrap_or_else(today);
+    // A date outside this week needs its own week's timetable, which is also
+    // the only one `today` can use
+    let date_week = Some(date).filter(|d| d.iso_week() != today().iso_week());
+    let query = TimetableQuery {
+        week: match command {
+            Some(Command::Today | Command::Next { .. }) => date_week,
+            _ => cli.week.or(date_week),
+        },
+        semester: cli.semester,
+    };
+
+    if let Some(Command::Next { watch }) = command {
+        let unsupported = match cli.format {
+            Format::Table => None,
+            Format::Json if !watch => None,
+            Format::Json => Some("--watch only works with the table format"),
+            _ => Some("`next` only works with the table and json formats"),
+        };
+        if let Some(message) = unsupported {
+            return Err(Error::Config {
+                setting: "--format".to_string(),
+                message: message.to_string(),
+            });
+        }
+    }
+    if cli.format != Format::Table {
+        colored::control::set_override(false);
     }
 
-    let username = user_config.get_username().unwrap();
-    let password = user_config.get_password().unwrap();
+    dotenv().ok();
+    let daily_quote_api = env::var("DAILY_QUOTE_API").ok();
 
-    let bar = ProgressBar::new_spinner();
+    // Load environment variables from .env file
+    let profiles: Vec<(String, UserConfig)> = if cli.profile.is_empty() {
+        vec![("default".to_string(), UserConfig::from_env())]
+    } else {
+        cli.profile
+            .iter()
+            .map(|name| (name.clone(), UserConfig::from_profile(name)))
+            .collect()
+    };
+
+    let mut retry = RetryPolicy::from_env()?;
+    retry.timeout = cli.timeout.unwrap_or(retry.timeout);
+    retry.retries = cli.retries.unwrap_or(retry.retries);
+    retry.deadline = cli.deadline.or(retry.deadline);
+
+    let bells = BellSchedule::from_env();
+    let grouped = profiles.len() > 1;
+
+    // A profile that isn't set up right fails on its own, like one that
+    // can't log in, and the others still run
+    let mut builders = Vec::new();
+    for (name, user_config) in &profiles {
+        if let Err(e) = user_config.validate() {
+            builders.push(Err(e));
+            continue;
+        }
+        let base_url = match &cli.base_url {
+            Some(url) => url.clone(),
+            None => match user_config.get_base_url().map(|u| parse_base_url(u)) {
+                Some(Ok(url)) => url,
+                Some(Err(message)) => {
+                    builders.push(Err(Error::Config {
+                        setting: user_config.base_url_var(),
+                        message,
+                    }));
+                    continue;
+                }
+                None => Url::parse(DEFAULT_BASE_URL).unwrap(),
+            },
+        };
+
+        let spinner = bar.clone();
+        let label = if grouped {
+            format!("[{}] ", name)
+        } else {
+            String::new()
+        };
+        let mut builder = UdaClient::builder()
+            .retry(retry)
+            .on_request(move |event| spinner.set_message(format!("{}{}", label, event)));
+        if let Some(path) = default_cookie_path(&base_url, user_config.get_username().unwrap()) {
+            builder = builder.cookie_file(path);
+        }
+        builder = builder.base_url(base_url);
+        builders.push(Ok(builder));
+    }
 
     bar.enable_steady_tick(Duration::from_millis(5));
+    bar.set_message("Login");
 
-    let cookie_store = Arc::new(Jar::default());
-    let client = Client::builder()
-        .cookie_provider(cookie_store.clone())
-        .build()?;
+    // Every profile logs in and fetches on its own, so one failing doesn't
+    // hold up or abort the others
+    let fetches = profiles
+        .iter()
+        .zip(builders)
+        .map(|((_, user_config), builder)| {
+            fetch_profile(builder, user_config, &query, command, date)
+        });
+    let (quote, fetched) = tokio::join!(
+        async {
+            match (&daily_quote_api, command) {
+                (Some(api), None) if cli.format == Format::Table => Some(get_quote(api).await),
+                _ => None,
+            }
+        },
+        join_all(fetches)
+    );
+
+    bar.set_message("Displaying results...");
+    bar.finish_and_clear();
+
+    let Some(layout) = Layout::of(cli.format) else {
+        let mut outcome = Outcome::default();
+        let mut report = Report::new(date);
+        for ((name, user_config), fetched) in profiles.iter().zip(&fetched) {
+            let username = user_config.get_username().map_or("", String::as_str);
+            let mut profile = ProfileReport::new(name, username);
+            match fetched {
+                Ok(fetched) => report_profile(
+                    fetched,
+                    &query,
+                    command,
+                    date,
+                    &bells,
+                    &mut outcome,
+                    &mut profile,
+                ),
+                Err(e @ Error::Config { .. }) => {
+                    report_failure(&mut profile, &mut outcome, "profile", e)
+                }
+                Err(e) => report_failure(&mut profile, &mut outcome, "login", e),
+            }
+            report.profiles.push(profile);
+        }
+        println!("{}", report.to_json());
+        return Ok(outcome.exit_code());
+    };
+    let mut sections = Sections::new(layout);
+
+    // A single profile that can't log in leaves nothing to show
+    let mut fetched = fetched;
+    if let [Err(_)] = fetched.as_slice() {
+        return fetched.pop().unwrap().map(|_| 0);
+    }
 
-    let login_url = "https://my.uda.edu.vn/sv/svlogin";
-    let timetable_url = "https://my.uda.edu.vn/sv/tkb";
-    let exam_schedule_url = "https://my.uda.edu.vn/sv/lichthi";
+    if let Some(Command::Next { watch: true }) = command {
+        watch_next(&profiles, &fetched, date, &bells, &mut sections).await;
+        return Ok(sections.exit_code());
+    }
+    for ((name, user_config), fetched) in profiles.iter().zip(&fetched) {
+        if grouped {
+            sections.heading(&format!(
+                "{} ({})",
+                name,
+                user_config.get_username().map_or("", String::as_str)
+            ));
+        }
+        match fetched {
+            Ok(fetched) => render_profile(fetched, &query, command, date, &bells, &mut sections),
+            Err(e) => sections.failed(&format!("Profile {}", name), e),
+        }
+    }
 
-    bar.set_message("Waiting for my.uda.edu.vn/sv/svlogin response...");
-    // let login_page = client.get(login_url).send().await?.text().await?;
+    sections.finish();
 
-    let mut form = HashMap::new();
+    // The quote and farewell only come with the full output, so a command
+    // can feed a status bar
+    if command.is_some() || cli.format != Format::Table {
+        return Ok(sections.exit_code());
+    }
 
-    form.insert("User", username.as_str());
-    form.insert("Password", password.as_str());
-    form.insert("__EVENTTARGET", "Lnew1");
-    // form.insert("__EVENTARGUMENT", "");
-    // form.insert("__VIEWSTATEGENERATOR", "C9E6EC0D");
+    match quote {
+        Some(Ok(q)) => {
+            println!(
+                "{} - {}",
+                q.quote.bright_green().bold(),
+                q.author.magenta().italic()
+            );
+            sections.shown();
+        }
+        Some(Err(e)) => {
+            sections.failed_with("Quote", &e, 3);
+            eprintln!("Talk is cheap, so me the code - Linus Torvalds");
+        }
+        // No quote is configured: nothing to show and nothing failed
+        None => {}
+    }
 
-    bar.set_message("Login");
-    // Post login request with assigned form of data
-    let resp = client
-        .post(login_url)
-        .header("Content-Type", "application/x-www-form-urlencoded")
-        .header("Host", "my.uda.edu.vn")
-        .header("Origin", "https://my.uda.edu.vn")
-        .header("Referer", "https://my.uda.edu.vn/sv/svlogin")
-        .header(
-            "User-Agent",
-            "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36",
-        )
-        .header("Connection", "keep-alive")
-        .form(&form)
-        .send()
-        .await?;
+    println!("{}", "◕‿◕) GOODBYE!!!".black().on_white());
 
-    // let quote = get_quote(&daily_quote_api).await;
-    // If login successfully
-    if resp.status().is_success() {
-        bar.set_message("Login successfully, getting information");
-        let (quote, resp_timetable, resp_exam_schedule) = tokio::join!(
-            get_quote(&daily_quote_api),
-            client.get(timetable_url).send(),
-            client.get(exam_schedule_url).send()
-        );
+    Ok(sections.exit_code())
+}
 
-        let timetable = resp_timetable?;
-        let exam = resp_exam_schedule?;
+/// What was fetched for one profile. Pages the command doesn't need are
+/// `None`.
+struct Fetched {
+    timetable: Option<Result<TimetablePage, Error>>,
+    exams: Option<Result<ExamPage, Error>>,
+}
 
-        if !timetable.status().is_success() {
-            eprintln!("Failed to fetch timetable: {}", timetable.status());
-            return Ok(());
+const TIMETABLE: &str = "Thời khóa biểu (Timetable)";
+const EXAMS: &str = "Thông báo thi (Exam schedule notice)";
+
+/// Logs in as `user_config` and fetches the pages `command` needs. Fails
+/// only if the profile isn't set up right (`builder` is an error), the
+/// client can't be built or the login fails.
+///
+/// If the week of `date` isn't in the portal's week picker, the default
+/// timetable page is fetched instead, its official timetable replaced by
+/// [`Error::NoSuchWeek`]: its upcoming schedule may still cover `date` (see
+/// [`official_sessions`]). Only the `week` command fails outright.
+async fn fetch_profile(
+    builder: Result<UdaClientBuilder, Error>,
+    user_config: &UserConfig,
+    query: &TimetableQuery,
+    command: Option<Command>,
+    date: NaiveDate,
+) -> Result<Fetched, Error> {
+    let client = builder?.build()?;
+    let username = user_config.get_username().unwrap();
+    let password = user_config.get_password().unwrap();
+    client.resume_session(username, password).await?;
+
+    let needs_timetable = command != Some(Command::Exams);
+    let needs_exams = matches!(command, None | Some(Command::Exams | Command::Next { .. }));
+    let (timetable, exams) = tokio::join!(
+        async {
+            if !needs_timetable {
+                return None;
+            }
+            Some(match client.fetch_timetable_for(query).await {
+                Err(Error::NoSuchWeek(week)) if week == date && command != Some(Command::Week) => {
+                    let query = TimetableQuery {
+                        week: None,
+                        semester: query.semester.clone(),
+                    };
+                    client
+                        .fetch_timetable_for(&query)
+                        .await
+                        .map(|page| TimetablePage {
+                            sessions: Err(Error::NoSuchWeek(week)),
+                            ..page
+                        })
+                }
+                page => page,
+            })
+        },
+        async {
+            if needs_exams {
+                Some(client.fetch_exams().await)
+            } else {
+                None
+            }
         }
-        if !exam.status().is_success() {
-            eprintln!("Failed to fetch exam schedule: {}", exam.status());
-            return Ok(());
+    );
+    Ok(Fetched { timetable, exams })
+}
+
+/// Prints the summary and the tables `command` asks for of one profile.
+fn render_profile(
+    fetched: &Fetched,
+    query: &TimetableQuery,
+    command: Option<Command>,
+    date: NaiveDate,
+    bells: &BellSchedule,
+    sections: &mut Sections,
+) {
+    if let Some(Command::Next { .. }) = command {
+        let schedule = Schedule::new(fetched);
+        for (title, e) in &schedule.errors {
+            sections.failed(title, e);
+        }
+        let after = starting_point(date);
+        match schedule.next_event(bells, after) {
+            Some(event) => println!(
+                "{}",
+                next_event_line(&event, countdown_from(date, after)).bold()
+            ),
+            // Nothing found in what could be fetched says nothing about the rest
+            None if !schedule.errors.is_empty() => return,
+            None => println!("{}", NOTHING_NEXT),
         }
+        sections.shown();
+        return;
+    }
+    if let Some(timetable) = &fetched.timetable {
+        render_timetable(timetable, query, command, date, bells, sections);
+    }
+    if let Some(exams) = &fetched.exams {
+        render_exams(exams, date, sections);
+    }
+}
 
-        let resp_timetable_text = timetable.text_with_charset("utf-8").await?;
-        let resp_exam_text = exam.text_with_charset("utf-8").await?;
+/// The official classes to go by for the day asked for. There are none when
+/// its week isn't in the week picker (see [`fetch_profile`]), and the
+/// upcoming schedule alone covers the day.
+fn official_sessions(page: &TimetablePage) -> Result<&[ClassSession], &Error> {
+    match &page.sessions {
+        Ok(sessions) => Ok(sessions),
+        Err(Error::NoSuchWeek(_)) => Ok(&[]),
+        Err(e) => Err(e),
+    }
+}
 
-        if resp_timetable_text.is_empty() {
-            eprintln!("Timetable response is empty");
-            return Ok(());
+/// Prints the sections that come from the timetable page.
+fn render_timetable(
+    timetable: &Result<TimetablePage, Error>,
+    query: &TimetableQuery,
+    command: Option<Command>,
+    date: NaiveDate,
+    bells: &BellSchedule,
+    sections: &mut Sections,
+) {
+    // Cancellations are matched against the dates of the week being shown
+    let week = query.week.unwrap_or(date);
+    // Rows left out of the grids only matter to the sections showing them
+    if let Ok(page) = timetable {
+        let shows_grid = [Command::Today, Command::Week, Command::Upcoming]
+            .into_iter()
+            .any(|section| shows(command, section));
+        if shows_grid {
+            for e in &page.warnings {
+                sections.failed(TIMETABLE, e);
+            }
+        }
+    }
+    let no_notices = Vec::new();
+    let notices = timetable.as_ref().map_or(&no_notices, |page| &page.notices);
+
+    match timetable.as_ref().and_then(official_sessions) {
+        Ok(sessions) if week.iso_week() == date.iso_week() && shows(command, Command::Today) => {
+            let upcoming = timetable
+                .as_ref()
+                .ok()
+                .and_then(|page| page.upcoming.as_ref().ok())
+                .map_or(&[][..], Vec::as_slice);
+            let is_today = date == today();
+            if sections.layout == Layout::Table {
+                let day = if is_today {
+                    "TODAY".to_string()
+                } else {
+                    date.format("%a %d/%m/%Y").to_string().to_uppercase()
+                };
+                println!(
+                    "{}",
+                    today_summary(sessions, upcoming, notices, bells, date, &day)
+                );
+            } else {
+                let title = if is_today {
+                    "Hôm nay (Today)".to_string()
+                } else {
+                    format!("Ngày {}", date.format("%d/%m/%Y"))
+                };
+                let table = today_table(sessions, upcoming, notices, bells, date);
+                sections.table(&title, &table, "Không có lịch học");
+            }
+            if command.is_some() {
+                sections.shown();
+            }
         }
+        Err(e) if command == Some(Command::Today) => sections.failed("Hôm nay (Today)", e),
+        _ => {}
+    }
 
-        // (HTML objects are not thread-safe due to internal Cell usage)
-        let html_timetable = Html::parse_document(&resp_timetable_text);
-        let html_exam = Html::parse_document(&resp_exam_text);
+    if shows(command, Command::Week) {
+        match timetable.as_ref().map(|page| &page.sessions) {
+            Ok(Ok(sessions)) => {
+                sections.table(
+                    "Thời khóa biểu chính thức (Official schedule)",
+                    &timetable_table(sessions, notices, bells, week),
+                    "Thời khóa biểu trống",
+                );
+                sections.shown();
+            }
+            Ok(Err(e)) | Err(e) => {
+                sections.failed("Thời khóa biểu chính thức (Official schedule)", e)
+            }
+        }
+    }
 
-        // Create selectors once
-        let tr = Selector::parse("tr").unwrap();
-        let td = Selector::parse("td").unwrap();
+    if shows(command, Command::Upcoming) {
+        match timetable.as_ref().map(|page| &page.upcoming) {
+            Ok(Ok(upcoming)) => {
+                sections.table(
+                    "Thời khóa biểu sắp tới (Upcoming schedule)",
+                    &upcoming_schedule_table(upcoming, date),
+                    "Thời khóa biểu sắp tới trống",
+                );
+                sections.shown();
+            }
+            Ok(Err(e)) | Err(e) => sections.failed("Thời khóa biểu sắp tới (Upcoming schedule)", e),
+        }
+    }
 
-        let announcement_table = cancellation_notice(&html_timetable, &tr, &td);
-        let upcoming_schedule = extract_upcoming_schedule(&html_timetable, &tr, &td);
-        let timetable_table = timetable_table(html_timetable, tr.clone(), td.clone());
-        let exam_schedule_table = exam_schedule(&html_exam, &tr, &td);
+    if shows(command, Command::Notices) {
+        match timetable {
+            Ok(page) => {
+                sections.table(
+                    "Thông báo nghỉ (Cancellation schedule notice)",
+                    &cancellation_notice(&page.notices),
+                    "Không có thông báo nghỉ",
+                );
+                sections.shown();
+            }
+            Err(e) => sections.failed("Thông báo nghỉ (Cancellation schedule notice)", e),
+        }
+    }
+}
 
-        bar.set_message("Displaying results...");
-        bar.finish_and_clear();
+fn render_exams(exams: &Result<ExamPage, Error>, date: NaiveDate, sections: &mut Sections) {
+    match exams {
+        Ok(page) => {
+            for e in &page.warnings {
+                sections.failed(EXAMS, e);
+            }
+            sections.table(
+                EXAMS,
+                &exam_schedule(&page.exams, date),
+                "Không có thông báo thi",
+            );
+            sections.shown();
+        }
+        Err(e) => sections.failed(EXAMS, e),
+    }
+}
 
-        if timetable_table.len() > 1 {
-            println!("Thời khóa biểu chính thức (Official schedule)");
-            timetable_table.printstd();
-        } else {
-            println!("Thời khóa biểu trống");
+/// Adds the sections `command` asks for of one profile to its JSON report.
+fn report_profile(
+    fetched: &Fetched,
+    query: &TimetableQuery,
+    command: Option<Command>,
+    date: NaiveDate,
+    bells: &BellSchedule,
+    outcome: &mut Outcome,
+    profile: &mut ProfileReport,
+) {
+    if let Some(Command::Next { .. }) = command {
+        let schedule = Schedule::new(fetched);
+        for (_, e) in &schedule.errors {
+            report_failure(profile, outcome, "next", e);
+        }
+        let event = schedule.next_event(bells, starting_point(date));
+        if event.is_some() || schedule.errors.is_empty() {
+            profile.next(event.as_ref());
+            outcome.shown();
         }
+        return;
+    }
 
-        if upcoming_schedule.len() > 1 {
-            println!("Thời khóa biểu sắp tới (Upcoming schedule)");
-            upcoming_schedule.printstd();
-        } else {
-            println!("Thời khóa biểu sắp tới trống");
+    if let Some(timetable) = &fetched.timetable {
+        let week = query.week.unwrap_or(date);
+        let shows_today = week.iso_week() == date.iso_week() && shows(command, Command::Today);
+        let shows_week = shows(command, Command::Week);
+        let page = match timetable {
+            Ok(page) => page,
+            Err(e) => {
+                let requested = [
+                    ("today", shows_today),
+                    ("week", shows_week),
+                    ("upcoming", shows(command, Command::Upcoming)),
+                    ("notices", shows(command, Command::Notices)),
+                ];
+                for (section, requested) in requested {
+                    if requested {
+                        report_failure(profile, outcome, section, e);
+                    }
+                }
+                return report_exams(fetched, date, outcome, profile);
+            }
+        };
+        if shows_today || shows_week || shows(command, Command::Upcoming) {
+            for e in &page.warnings {
+                report_failure(profile, outcome, "timetable", e);
+            }
         }
 
-        if announcement_table.len() > 1 {
-            println!("Thông báo nghỉ (Cancellation schedule notice)");
-            announcement_table.printstd();
-        } else {
-            println!("Không có thông báo nghỉ");
+        match official_sessions(page) {
+            Ok(sessions) if shows_today => {
+                let upcoming = page.upcoming.as_ref().map_or(&[][..], Vec::as_slice);
+                profile.today(sessions, upcoming, &page.notices, bells, date);
+                outcome.shown();
+            }
+            Err(e) if shows_today => report_failure(profile, outcome, "today", e),
+            _ => {}
         }
+        if shows_week {
+            match &page.sessions {
+                Ok(sessions) => {
+                    profile.week(sessions, &page.notices, bells, week);
+                    outcome.shown();
+                }
+                Err(e) => report_failure(profile, outcome, "week", e),
+            }
+        }
+        if shows(command, Command::Upcoming) {
+            match &page.upcoming {
+                Ok(upcoming) => {
+                    profile.upcoming(upcoming, bells, date);
+                    outcome.shown();
+                }
+                Err(e) => report_failure(profile, outcome, "upcoming", e),
+            }
+        }
+        if shows(command, Command::Notices) {
+            profile.notices(&page.notices);
+            outcome.shown();
+        }
+    }
+    report_exams(fetched, date, outcome, profile);
+}
 
-        if exam_schedule_table.len() > 1 {
-            println!("Thông báo thi (Exam schedule notice)");
-            exam_schedule_table.printstd();
-        } else {
-            println!("Không có thông báo thi");
+fn report_exams(
+    fetched: &Fetched,
+    date: NaiveDate,
+    outcome: &mut Outcome,
+    profile: &mut ProfileReport,
+) {
+    match &fetched.exams {
+        Some(Ok(page)) => {
+            for e in &page.warnings {
+                report_failure(profile, outcome, "exams", e);
+            }
+            profile.exams(&page.exams, date);
+            outcome.shown();
         }
+        Some(Err(e)) => report_failure(profile, outcome, "exams", e),
+        None => {}
+    }
+}
 
+/// Lists `e` under `section` in the JSON report instead of printing it.
+fn report_failure(profile: &mut ProfileReport, outcome: &mut Outcome, section: &str, e: &Error) {
+    let code = exit_code(e);
+    profile.failed(section, code, e);
+    outcome.failed(code);
+}
 
-        match quote {
-            Ok(q) => println!(
-                "{} - {}",
-                q.quote.bright_green().bold(),
-                q.author.magenta().italic()
-            ),
-            Err(_) => eprintln!("Talk is cheap, so me the code - Linus Torvalds"),
+/// Where `next` starts looking: now, or the start of `date` if another day
+/// was asked for.
+fn starting_point(date: NaiveDate) -> NaiveDateTime {
+    if date == today() {
+        now()
+    } else {
+        date.and_time(chrono::NaiveTime::MIN)
+    }
+}
+
+/// What `next` counts down from: `after`, the point it started looking
+/// from, when that is now. Another day has no countdown, as the time left
+/// from its start says nothing.
+fn countdown_from(date: NaiveDate, after: NaiveDateTime) -> Option<NaiveDateTime> {
+    (date == today()).then_some(after)
+}
+
+const NOTHING_NEXT: &str = "Không có lịch học hay lịch thi sắp tới";
+
+/// The classes and exams of one profile that `next` picks from. Pages that
+/// failed are reported and count as empty, and rows left out of them are
+/// reported too.
+struct Schedule<'a> {
+    sessions: &'a [ClassSession],
+    upcoming: &'a [UpcomingClass],
+    notices: &'a [CancellationNotice],
+    exams: &'a [ExamEntry],
+    /// Pages that failed and rows that were left out, by section title.
+    errors: Vec<(&'static str, &'a Error)>,
+}
+
+impl<'a> Schedule<'a> {
+    fn new(fetched: &'a Fetched) -> Self {
+        let mut schedule = Schedule {
+            sessions: &[],
+            upcoming: &[],
+            notices: &[],
+            exams: &[],
+            errors: Vec::new(),
+        };
+        match &fetched.timetable {
+            Some(Ok(page)) => {
+                schedule.notices = &page.notices;
+                schedule
+                    .errors
+                    .extend(page.warnings.iter().map(|e| (TIMETABLE, e)));
+                match official_sessions(page) {
+                    Ok(sessions) => schedule.sessions = sessions,
+                    Err(e) => schedule
+                        .errors
+                        .push(("Thời khóa biểu chính thức (Official schedule)", e)),
+                }
+                match &page.upcoming {
+                    Ok(upcoming) => schedule.upcoming = upcoming,
+                    Err(e) => schedule
+                        .errors
+                        .push(("Thời khóa biểu sắp tới (Upcoming schedule)", e)),
+                }
+            }
+            Some(Err(e)) => schedule.errors.push((TIMETABLE, e)),
+            None => {}
+        }
+        match &fetched.exams {
+            Some(Ok(page)) => {
+                schedule.exams = &page.exams;
+                schedule
+                    .errors
+                    .extend(page.warnings.iter().map(|e| (EXAMS, e)));
+            }
+            Some(Err(e)) => schedule.errors.push((EXAMS, e)),
+            None => {}
         }
+        schedule
+    }
 
-        println!("{}", "◕‿◕) GOODBYE!!!".black().on_white());
+    fn next_event(&self, bells: &BellSchedule, after: NaiveDateTime) -> Option<Event> {
+        next_event(
+            self.sessions,
+            self.upcoming,
+            self.exams,
+            self.notices,
+            bells,
+            after,
+        )
+    }
+}
 
-        return Ok(());
-    } else {
-        eprintln!("Failed: {}", resp.status());
+/// `next --watch`: one line per profile, counting down every second. When
+/// an event starts, the line moves on to the one after it. Returns once no
+/// profile has anything left.
+async fn watch_next(
+    profiles: &[(String, UserConfig)],
+    fetched: &[Result<Fetched, Error>],
+    date: NaiveDate,
+    bells: &BellSchedule,
+    sections: &mut Sections,
+) {
+    let lines = MultiProgress::new();
+    let style = ProgressStyle::with_template("{msg}").unwrap();
+    let mut watched = Vec::new();
+    for ((name, _), fetched) in profiles.iter().zip(fetched) {
+        let fetched = match fetched {
+            Ok(fetched) => fetched,
+            Err(e) => {
+                sections.failed(&format!("Profile {}", name), e);
+                continue;
+            }
+        };
+        let schedule = Schedule::new(fetched);
+        for (title, e) in &schedule.errors {
+            sections.failed(title, e);
+        }
+        let event = schedule.next_event(bells, starting_point(date));
+        if event.is_none() && !schedule.errors.is_empty() {
+            continue;
+        }
+        let label = if profiles.len() > 1 {
+            format!("[{}] ", name)
+        } else {
+            String::new()
+        };
+        let line = lines.add(ProgressBar::new_spinner().with_style(style.clone()));
+        sections.shown();
+        watched.push((line, label, schedule, event));
     }
 
-    Ok(())
+    // Another day has no countdown to keep up, so its lines are shown once
+    let live = date == today();
+    let mut ticks = tokio::time::interval(Duration::from_secs(1));
+    loop {
+        ticks.tick().await;
+        let now = now();
+        for (line, label, schedule, event) in &mut watched {
+            if live && event.as_ref().is_some_and(|e| !e.is_upcoming(now)) {
+                *event = schedule.next_event(bells, now);
+            }
+            match event {
+                Some(event) if live => {
+                    line.set_message(format!("{}{}", label, next_event_line(event, Some(now))))
+                }
+                Some(event) => {
+                    line.finish_with_message(format!("{}{}", label, next_event_line(event, None)))
+                }
+                None => line.finish_with_message(format!("{}{}", label, NOTHING_NEXT)),
+            }
+        }
+        if !live || watched.iter().all(|(_, _, _, event)| event.is_none()) {
+            return;
+        }
+    }
 }
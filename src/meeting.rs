@@ -0,0 +1,226 @@
+use reqwest::Url;
+use std::fmt;
+
+/// Video-conferencing service an online class is held on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Platform {
+    GoogleMeet,
+    Zoom,
+    MicrosoftTeams,
+    Other,
+}
+
+impl Platform {
+    /// Classifies a meeting URL by its host.
+    pub fn from_url(url: &Url) -> Self {
+        let host = url.host_str().unwrap_or("").to_lowercase();
+        if host == "meet.google.com" {
+            Platform::GoogleMeet
+        } else if host == "zoom.us" || host.ends_with(".zoom.us") {
+            Platform::Zoom
+        } else if host == "teams.microsoft.com" || host == "teams.live.com" {
+            Platform::MicrosoftTeams
+        } else {
+            Platform::Other
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Platform::GoogleMeet => "Google Meet",
+            Platform::Zoom => "Zoom",
+            Platform::MicrosoftTeams => "Microsoft Teams",
+            Platform::Other => "Other",
+        }
+    }
+}
+
+impl fmt::Display for Platform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Meeting details of an online class, taken from the room cell's anchor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OnlineMeeting {
+    pub url: String,
+    pub platform: Platform,
+    pub meeting_id: Option<String>,
+    pub passcode: Option<String>,
+}
+
+impl OnlineMeeting {
+    /// Builds a meeting from an anchor's `href` and the text around it.
+    ///
+    /// Relative hrefs are resolved against `base`. The portal sometimes leaks
+    /// the next attribute (`target=...`) into the href, which is cut off.
+    /// Returns `None` if no usable URL is left.
+    pub fn from_anchor(href: &str, text: &str, base: &Url) -> Option<Self> {
+        let url = base.join(&clean_href(href)?).ok()?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return None;
+        }
+        let platform = Platform::from_url(&url);
+
+        let meeting_id = find_labeled(text, &["meeting id", "id phòng", "id"], |c| {
+            c.is_ascii_digit() || c == ' '
+        })
+        .or_else(|| id_from_url(&url, platform));
+        let passcode = find_labeled(
+            text,
+            &["passcode", "password", "mật khẩu", "pass", "pwd"],
+            |c| !c.is_whitespace() && !matches!(c, ',' | ';' | ')'),
+        );
+
+        Some(Self {
+            url: url.to_string(),
+            platform,
+            meeting_id,
+            passcode,
+        })
+    }
+}
+
+impl fmt::Display for OnlineMeeting {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.platform {
+            Platform::Other => write!(f, "link online ({})", self.url)?,
+            platform => write!(f, "{} ({})", platform, self.url)?,
+        }
+        if let Some(id) = &self.meeting_id {
+            write!(f, " ID: {}", id)?;
+        }
+        if let Some(passcode) = &self.passcode {
+            write!(f, " Pass: {}", passcode)?;
+        }
+        Ok(())
+    }
+}
+
+fn clean_href(href: &str) -> Option<String> {
+    let href = href.trim().trim_matches(['"', '\'']);
+    let mut end = href
+        .find(|c: char| c.is_whitespace() || c == '"' || c == '\'')
+        .unwrap_or(href.len());
+    if let Some(i) = href[..end].find("target=") {
+        end = i;
+    }
+    let mut href = href[..end].to_string();
+    for stray in ["target", "targe"] {
+        if let Some(stripped) = href.strip_suffix(stray) {
+            href = stripped.to_string();
+        }
+    }
+    let href = href.trim_end_matches(['&', '?']);
+    (!href.is_empty() && href != "#").then(|| href.to_string())
+}
+
+/// Value following one of `labels` (case-insensitive, optional ':'), made of
+/// the characters accepted by `accept`. A label only counts as a whole word,
+/// so "id" isn't found inside "video", and every place it appears is tried.
+fn find_labeled(text: &str, labels: &[&str], accept: impl Fn(char) -> bool) -> Option<String> {
+    let lower = text.to_lowercase();
+    labels.iter().find_map(|label| {
+        lower.match_indices(label).find_map(|(i, _)| {
+            let start = i + label.len();
+            let is_word_char = |c: Option<char>| c.is_some_and(char::is_alphanumeric);
+            if is_word_char(lower[..i].chars().next_back())
+                || is_word_char(lower[start..].chars().next())
+            {
+                return None;
+            }
+            let rest = lower[start..].trim_start_matches([':', ' ']);
+            // Take the value from `text` to keep its casing, unless lowercasing
+            // changed the byte length and the offsets no longer line up
+            let original = if lower.len() == text.len() {
+                text.get(text.len() - rest.len()..)?
+            } else {
+                rest
+            };
+            let value: String = original.chars().take_while(|c| accept(*c)).collect();
+            let value = value.trim();
+            (!value.is_empty()).then(|| value.to_string())
+        })
+    })
+}
+
+fn id_from_url(url: &Url, platform: Platform) -> Option<String> {
+    let mut segments = url.path_segments()?;
+    match platform {
+        // meet.google.com/abc-defg-hij
+        Platform::GoogleMeet => segments
+            .next()
+            .filter(|s| !s.is_empty())
+            .map(str::to_string),
+        // zoom.us/j/1234567890
+        Platform::Zoom => {
+            let id = segments.skip_while(|s| *s != "j").nth(1)?;
+            id.chars()
+                .all(|c| c.is_ascii_digit())
+                .then(|| id.to_string())
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clean_href_cuts_leaked_attributes() {
+        let meet = Some("https://meet.google.com/abc-defg-hij".to_string());
+        assert_eq!(clean_href("https://meet.google.com/abc-defg-hij"), meet);
+        assert_eq!(
+            clean_href(" \"https://meet.google.com/abc-defg-hij\" "),
+            meet
+        );
+        assert_eq!(
+            clean_href("https://meet.google.com/abc-defg-hij\" target=\"_blank"),
+            meet
+        );
+        assert_eq!(
+            clean_href("https://meet.google.com/abc-defg-hijtarget=_blank"),
+            meet
+        );
+        assert_eq!(
+            clean_href("https://zoom.us/j/1234567890?pwd=xyz&target").as_deref(),
+            Some("https://zoom.us/j/1234567890?pwd=xyz")
+        );
+    }
+
+    #[test]
+    fn clean_href_rejects_empty_links() {
+        assert_eq!(clean_href("#"), None);
+        assert_eq!(clean_href("  "), None);
+        assert_eq!(clean_href("target=_blank"), None);
+    }
+
+    #[test]
+    fn meeting_details_come_from_the_anchor_text() {
+        let base = Url::parse("https://my.uda.edu.vn/sv/tkb").unwrap();
+        let meeting = OnlineMeeting::from_anchor(
+            "https://zoom.us/j/1234567890 target=",
+            "Online Vào học (Meeting ID: 123 456 7890, Passcode: xyz)",
+            &base,
+        )
+        .unwrap();
+        assert_eq!(meeting.platform, Platform::Zoom);
+        assert_eq!(meeting.meeting_id.as_deref(), Some("123 456 7890"));
+        assert_eq!(meeting.passcode.as_deref(), Some("xyz"));
+    }
+
+    #[test]
+    fn labels_are_matched_as_whole_words() {
+        let base = Url::parse("https://my.uda.edu.vn/sv/tkb").unwrap();
+        let meeting = OnlineMeeting::from_anchor(
+            "https://lms.uda.edu.vn/room/42",
+            "Online video ID 123, bypass nothing, pass: k1",
+            &base,
+        )
+        .unwrap();
+        assert_eq!(meeting.meeting_id.as_deref(), Some("123"));
+        assert_eq!(meeting.passcode.as_deref(), Some("k1"));
+    }
+}
@@ -0,0 +1,417 @@
+use crate::course::CourseTitle;
+use crate::timetable::{ClassSession, Session};
+use chrono::{Datelike, NaiveDate};
+use scraper::{Html, Selector};
+
+/// Inclusive range of dates a notice applies to. A single day has
+/// `start == end`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateRange {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+}
+
+impl DateRange {
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.start <= date && date <= self.end
+    }
+}
+
+/// One row of the "Thông báo nghỉ" table (`#MainContent_Gtb`).
+///
+/// `time_text` and `content` always hold the original text, so a notice that
+/// couldn't be parsed is still shown to the user as-is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CancellationNotice {
+    /// Raw "Thời gian nghỉ".
+    pub time_text: String,
+    /// Raw "Nội dung nghỉ".
+    pub content: String,
+    /// Days and ranges of days parsed out of `time_text`. Two dates make a
+    /// range only when joined by "đến" or a dash; otherwise each is a day of
+    /// its own, e.g. "các ngày 19/08/2025 và 26/08/2025".
+    pub dates: Vec<DateRange>,
+    /// Session named in `time_text`; `None` means the whole day.
+    pub session: Option<Session>,
+    /// Affected course, as written after "học phần" / "môn".
+    pub course: Option<CourseTitle>,
+    /// Make-up ("học bù") date, if the content mentions one.
+    pub makeup_date: Option<NaiveDate>,
+}
+
+impl CancellationNotice {
+    /// Builds a notice from the two table columns, parsing what it can.
+    pub fn parse(time_text: &str, content: &str) -> Self {
+        Self {
+            time_text: time_text.to_string(),
+            content: content.to_string(),
+            dates: find_date_ranges(time_text),
+            session: find_session(time_text),
+            course: find_course(content),
+            makeup_date: find_makeup_date(content),
+        }
+    }
+
+    /// Whether the notice cancels `session` on `date`. Notices without a
+    /// parsed date never match.
+    pub fn applies_to(&self, date: NaiveDate, session: Session) -> bool {
+        self.dates.iter().any(|d| d.contains(date)) && self.session.is_none_or(|s| s == session)
+    }
+
+    /// Whether the notice is about `course`: the course it names has the
+    /// same key. Only when no name could be parsed is `course` looked for
+    /// anywhere in the content, since a short name such as "Tiếng Anh" is
+    /// also found inside "Tiếng Anh chuyên ngành".
+    pub fn mentions(&self, course: &CourseTitle) -> bool {
+        match &self.course {
+            Some(name) => !name.key.is_empty() && name.key == course.key,
+            None => course.is_mentioned_in(&self.content),
+        }
+    }
+}
+
+/// What a cancellation notice does to one dated class.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionStatus {
+    Unaffected,
+    Cancelled,
+    /// Cancelled, with a make-up class on the given date.
+    Moved(NaiveDate),
+}
+
+/// A timetable class on a concrete date.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DatedSession<'a> {
+    pub date: NaiveDate,
+    pub class: &'a ClassSession,
+    pub status: SessionStatus,
+}
+
+/// Status of `class` on `date` according to `notices`. The first notice that
+/// applies wins.
+pub fn session_status(
+    class: &ClassSession,
+    date: NaiveDate,
+    notices: &[CancellationNotice],
+) -> SessionStatus {
+    notices
+        .iter()
+        .find(|n| n.applies_to(date, class.session) && n.mentions(&class.course))
+        .map_or(SessionStatus::Unaffected, |n| match n.makeup_date {
+            Some(makeup) => SessionStatus::Moved(makeup),
+            None => SessionStatus::Cancelled,
+        })
+}
+
+/// Expands weekly `sessions` into every dated class between `from` and `to`
+/// (inclusive) and marks each one against `notices`.
+pub fn match_cancellations<'a>(
+    sessions: &'a [ClassSession],
+    notices: &[CancellationNotice],
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Vec<DatedSession<'a>> {
+    from.iter_days()
+        .take_while(|date| *date <= to)
+        .flat_map(|date| {
+            sessions
+                .iter()
+                .filter(move |class| class.weekday == date.weekday())
+                .map(move |class| DatedSession {
+                    date,
+                    class,
+                    status: session_status(class, date, notices),
+                })
+        })
+        .collect()
+}
+
+/// Finds every dd/mm/yyyy date in `s`, in order of appearance.
+pub(crate) fn find_dates(s: &str) -> Vec<NaiveDate> {
+    s.split(|c: char| !(c.is_ascii_digit() || c == '/'))
+        .filter_map(|token| NaiveDate::parse_from_str(token.trim_matches('/'), "%d/%m/%Y").ok())
+        .collect()
+}
+
+/// The dates in `s` as days, or as ranges where the text between two dates
+/// says so ("Từ 25/08/2025 đến 27/08/2025", "25/08/2025 - 27/08/2025").
+fn find_date_ranges(s: &str) -> Vec<DateRange> {
+    let mut dates = Vec::new();
+    let mut token_start = None;
+    for (i, c) in s.char_indices().chain([(s.len(), ' ')]) {
+        match (c.is_ascii_digit() || c == '/', token_start) {
+            (true, None) => token_start = Some(i),
+            (false, Some(start)) => {
+                let token = s[start..i].trim_matches('/');
+                if let Ok(date) = NaiveDate::parse_from_str(token, "%d/%m/%Y") {
+                    dates.push((start, i, date));
+                }
+                token_start = None;
+            }
+            _ => {}
+        }
+    }
+
+    let mut ranges: Vec<DateRange> = Vec::new();
+    let mut previous_end = None;
+    for (start, end, date) in dates {
+        let joined = previous_end.is_some_and(|previous_end| {
+            let between = s[previous_end..start].trim().to_lowercase();
+            matches!(between.as_str(), "đến" | "-" | "–")
+        });
+        match ranges.last_mut() {
+            Some(range) if joined => {
+                range.start = range.start.min(date);
+                range.end = range.end.max(date);
+            }
+            _ => ranges.push(DateRange {
+                start: date,
+                end: date,
+            }),
+        }
+        previous_end = Some(end);
+    }
+    ranges
+}
+
+fn find_session(s: &str) -> Option<Session> {
+    let s = s.to_lowercase();
+    [Session::Morning, Session::Afternoon, Session::Evening]
+        .into_iter()
+        .find(|session| s.contains(&session.label().to_lowercase()))
+}
+
+fn find_course(content: &str) -> Option<CourseTitle> {
+    let lower = content.to_lowercase();
+    let start = ["học phần", "môn học", "môn"]
+        .iter()
+        .find_map(|marker| lower.find(marker).map(|i| i + marker.len()))?;
+    // Byte offsets are shared because to_lowercase keeps Vietnamese letters
+    // the same length; bail out on the rare text where it doesn't.
+    let rest = content.get(start..)?.trim_start_matches([':', ' ']);
+    let rest_lower = rest.to_lowercase();
+    let stop_words = [
+        ",",
+        ";",
+        ".",
+        " do ",
+        " vì ",
+        " học bù",
+        " dạy bù",
+        " ngày",
+        " từ",
+        " (thứ",
+    ];
+    // A parenthesis ends the name unless it holds the credits, e.g. "(3tc)"
+    let parenthesis = rest_lower
+        .match_indices('(')
+        .map(|(i, _)| i)
+        .find(|&i| !rest_lower[i + 1..].starts_with(|c: char| c.is_ascii_digit()));
+    let end = stop_words
+        .iter()
+        .filter_map(|stop| rest_lower.find(stop))
+        .chain(parenthesis)
+        .min()
+        .unwrap_or(rest.len());
+    let course = rest.get(..end)?.trim();
+    (!course.is_empty()).then(|| CourseTitle::parse(course))
+}
+
+fn find_makeup_date(content: &str) -> Option<NaiveDate> {
+    let lower = content.to_lowercase();
+    let start = ["học bù", "dạy bù"]
+        .iter()
+        .filter_map(|marker| lower.find(marker))
+        .min()?;
+    find_dates(lower.get(start..)?).into_iter().next()
+}
+
+/// Parses the class cancellation notice table (`#MainContent_Gtb`).
+///
+/// This function iterates through table rows (`<tr>`) matching the `tr` selector,
+/// and for each row, it extracts the text from each table cell (`<td>`)
+/// matching the `td` selector.
+///
+/// # Arguments
+///
+/// * `html` - A reference to a parsed HTML document from the `scraper` crate.
+/// * `tr` - A `Selector` for the table rows (`<tr>`) that contain the cancellation data.
+/// * `td` - A `Selector` for the table data cells (`<td>`) within each selected row.
+pub fn parse_cancellation_notices(
+    html: &Html,
+    tr: &Selector,
+    td: &Selector,
+) -> Vec<CancellationNotice> {
+    let announcement = Selector::parse("#MainContent_Gtb").unwrap();
+    let mut notices = Vec::new();
+
+    if let Some(table) = html.select(&announcement).next() {
+        for row in table.select(tr) {
+            let row_data: Vec<_> = row
+                .select(td)
+                .map(|cell| cell.text().collect::<String>().trim().to_string())
+                .collect();
+            if row_data.len() > 1 {
+                notices.push(CancellationNotice::parse(&row_data[0], &row_data[1]));
+            }
+        }
+    }
+
+    notices
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn course_of(content: &str) -> Option<String> {
+        find_course(content).map(|c| c.to_string())
+    }
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%d/%m/%Y").unwrap()
+    }
+
+    #[test]
+    fn parses_single_day_with_session_and_makeup() {
+        let notice = CancellationNotice::parse(
+            "Thứ 3, 19/08/2025 (Sáng)",
+            "Nghỉ học phần Công nghệ IOT (3tc), học bù ngày 23/08/2025",
+        );
+        assert_eq!(
+            notice.dates,
+            vec![DateRange {
+                start: date("19/08/2025"),
+                end: date("19/08/2025"),
+            }]
+        );
+        assert_eq!(notice.session, Some(Session::Morning));
+        assert_eq!(
+            notice.course.map(|c| c.to_string()).as_deref(),
+            Some("Công nghệ IOT (3tc)")
+        );
+        assert_eq!(notice.makeup_date, Some(date("23/08/2025")));
+    }
+
+    #[test]
+    fn parses_date_range_for_the_whole_day() {
+        let notice = CancellationNotice::parse(
+            "Từ 25/08/2025 đến 27/08/2025",
+            "Nghỉ học phần Lập trình Web 2 do giảng viên đi công tác",
+        );
+        assert_eq!(
+            notice.dates,
+            vec![DateRange {
+                start: date("25/08/2025"),
+                end: date("27/08/2025"),
+            }]
+        );
+        assert_eq!(notice.session, None);
+        assert!(notice.applies_to(date("26/08/2025"), Session::Afternoon));
+        assert!(!notice.applies_to(date("28/08/2025"), Session::Morning));
+        assert_eq!(notice.makeup_date, None);
+    }
+
+    #[test]
+    fn separate_days_are_not_a_range() {
+        let notice = CancellationNotice::parse(
+            "Thứ 3 các ngày 19/08/2025 và 26/08/2025",
+            "Nghỉ học phần Công nghệ IOT (3tc)",
+        );
+        assert_eq!(notice.dates.len(), 2);
+        assert!(notice.applies_to(date("19/08/2025"), Session::Morning));
+        assert!(notice.applies_to(date("26/08/2025"), Session::Morning));
+        assert!(!notice.applies_to(date("21/08/2025"), Session::Morning));
+    }
+
+    #[test]
+    fn dash_between_dates_is_a_range() {
+        let notice =
+            CancellationNotice::parse("01/09/2025 - 03/09/2025 (Chiều)", "Nghỉ môn Đa văn hoá");
+        assert_eq!(
+            notice.dates,
+            vec![DateRange {
+                start: date("01/09/2025"),
+                end: date("03/09/2025"),
+            }]
+        );
+    }
+
+    #[test]
+    fn keeps_unparsed_notice_text() {
+        let notice = CancellationNotice::parse("Đang cập nhật", "Thông báo sau");
+        assert_eq!(notice.dates, Vec::new());
+        assert_eq!(notice.course, None);
+        assert_eq!(notice.time_text, "Đang cập nhật");
+        assert!(!notice.applies_to(date("19/08/2025"), Session::Morning));
+    }
+
+    #[test]
+    fn course_name_stops_before_dates_and_weekdays() {
+        assert_eq!(
+            course_of("Nghỉ học môn Lập trình Web 2 ngày 09/09/2025").as_deref(),
+            Some("Lập trình Web 2")
+        );
+        assert_eq!(
+            course_of("Nghỉ học phần Đa văn hoá từ 25/08/2025 đến 27/08/2025").as_deref(),
+            Some("Đa văn hoá")
+        );
+        assert_eq!(
+            course_of("Nghỉ môn Công nghệ IOT (thứ 3) do giảng viên bận").as_deref(),
+            Some("Công nghệ IOT")
+        );
+        assert_eq!(
+            course_of("Nghỉ môn Công nghệ IOT(Sáng thứ 3)").as_deref(),
+            Some("Công nghệ IOT")
+        );
+    }
+
+    #[test]
+    fn course_name_keeps_credits() {
+        assert_eq!(
+            course_of("Nghỉ học phần Công nghệ IOT (3tc), học bù ngày 23/08/2025").as_deref(),
+            Some("Công nghệ IOT (3tc)")
+        );
+        assert_eq!(
+            course_of("Học phần: Lập trình Web 2 (3 TC), giảng viên đi công tác").as_deref(),
+            Some("Lập trình Web 2 (3tc)")
+        );
+    }
+
+    #[test]
+    fn notice_mentions_course_with_date_after_name() {
+        let notice = CancellationNotice::parse(
+            "Thứ 3, 09/09/2025",
+            "Nghỉ học môn Lập trình Web 2 ngày 09/09/2025",
+        );
+        assert!(notice.mentions(&CourseTitle::parse("Lập trình Web 2 (3tc)")));
+        assert!(!notice.mentions(&CourseTitle::parse("Lập trình Web 1 (3tc)")));
+    }
+
+    #[test]
+    fn notice_mentions_falls_back_to_content() {
+        // "môn" is found inside "bộ môn", so the parsed name is wrong
+        let notice = CancellationNotice::parse(
+            "10/09/2025",
+            "Theo thông báo của bộ môn, nghỉ học phần Công nghệ IOT",
+        );
+        assert!(notice.mentions(&CourseTitle::parse("Công nghệ IOT (3tc)")));
+    }
+
+    #[test]
+    fn notice_without_course_name_searches_content() {
+        let notice = CancellationNotice::parse("10/09/2025", "Nghỉ Công nghệ IOT, giảng viên ốm");
+        assert_eq!(notice.course, None);
+        assert!(notice.mentions(&CourseTitle::parse("Công nghệ IOT (3tc)")));
+    }
+
+    #[test]
+    fn notice_does_not_mention_course_that_is_a_prefix() {
+        let notice = CancellationNotice::parse(
+            "Thứ 5, 11/09/2025",
+            "Nghỉ học phần Tiếng Anh chuyên ngành (2tc)",
+        );
+        assert!(notice.mentions(&CourseTitle::parse("Tiếng Anh chuyên ngành (2tc)")));
+        assert!(!notice.mentions(&CourseTitle::parse("Tiếng Anh (2tc)")));
+    }
+}
@@ -16,6 +16,5 @@ pub async fn get_quote(api: &str) -> Result<Quote, Error> {
         .header(AUTHORIZATION, format!("Bearer {}", api))
         .header(CONTENT_TYPE, "application/json")
         .send().await;
-    let quote = res?.json::<Quote>().await;
-    return quote;
+    res?.json::<Quote>().await
 }
@@ -0,0 +1,331 @@
+use crate::bell::BellSchedule;
+use crate::event::{Event, EventKind};
+use crate::exam::ExamEntry;
+use crate::notice::{session_status, CancellationNotice, SessionStatus};
+use crate::timetable::{weekday_number, ClassSession};
+use crate::upcoming::{classes_on, UpcomingClass};
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, Weekday};
+use colored::Colorize;
+use prettytable::{
+    color,
+    format::{self, FormatBuilder},
+    Attr, Cell, Row, Table,
+};
+
+#[macro_export]
+macro_rules! table_header {
+    ( $headers:expr ) => {{
+        use prettytable::color;
+
+        let mut table = Table::new();
+        let cells: Vec<Cell> = $headers
+            .iter()
+            .map(|x| {
+                Cell::new(x)
+                    .with_style(Attr::Bold)
+                    .with_style(Attr::ForegroundColor(color::GREEN))
+            })
+            .collect();
+        table.add_row(Row::new(cells));
+        table
+    }};
+}
+
+/// Columns of the official timetable, also used for the classes of one day.
+const TIMETABLE_HEADER: [&str; 7] = [
+    "THỨ",
+    "BUỔI",
+    "TIẾT",
+    "PHÒNG",
+    "HỌC PHẦN",
+    "GIẢNG VIÊN",
+    "LỚP HỌC TẬP",
+];
+
+/// Renders parsed [`CancellationNotice`]s with their original text.
+pub fn cancellation_notice(notices: &[CancellationNotice]) -> Table {
+    let header = ["Thời gian nghỉ", "Nội dung nghỉ"];
+    let mut announcement_table = table_header![header];
+
+    for notice in notices {
+        announcement_table.add_row(Row::new(vec![
+            Cell::new(&notice.time_text),
+            Cell::new(&notice.content),
+        ]));
+    }
+
+    announcement_table
+}
+
+/// Renders parsed [`ClassSession`]s as the official timetable table.
+///
+/// Each class is matched against `notices` for its date in the week of
+/// `today`; cancelled classes are struck through and moved ones show the
+/// make-up date.
+///
+/// # Arguments
+///
+/// * `sessions` - Classes returned by [`parse_timetable`](crate::parse_timetable).
+/// * `notices` - Notices returned by [`parse_cancellation_notices`](crate::parse_cancellation_notices).
+/// * `bells` - Bell schedule used to show the clock time of each period range.
+/// * `today` - Date whose week the classes are matched in.
+pub fn timetable_table(
+    sessions: &[ClassSession],
+    notices: &[CancellationNotice],
+    bells: &BellSchedule,
+    today: NaiveDate,
+) -> Table {
+    let mut table_pretty = table_header![TIMETABLE_HEADER];
+
+    for class in sessions {
+        let date = date_in_week(today, class.weekday);
+        let status = session_status(class, date, notices);
+        table_pretty.add_row(Row::new(session_cells(class, status, bells)));
+    }
+
+    let custom_table_format = FormatBuilder::new()
+        .column_separator('|')
+        .borders('╿')
+        .separators(
+            &[
+                format::LinePosition::Top,
+                format::LinePosition::Intern,
+                format::LinePosition::Bottom,
+            ],
+            // format::LineSeparator::new('─', '+', '+', '+'),
+            format::LineSeparator::new('─', '╋', '╋', '╋'),
+        )
+        .padding(1, 1)
+        .build();
+
+    table_pretty.set_format(custom_table_format);
+
+    table_pretty
+}
+
+/// Renders the "CLASSES FOR TODAY" banner: one line per class on `date`,
+/// or "NO CLASSES FOR TODAY" when there are none. Classes of the upcoming
+/// schedule that have started by `date` are included, so a date past the
+/// end of the fetched timetable still shows what will be taught. `day`
+/// names the date in the banner, e.g. "TODAY" or "MON 25/08/2025".
+pub fn today_summary(
+    sessions: &[ClassSession],
+    upcoming: &[UpcomingClass],
+    notices: &[CancellationNotice],
+    bells: &BellSchedule,
+    date: NaiveDate,
+    day: &str,
+) -> String {
+    let on_date = classes_on(sessions, upcoming, date);
+
+    let classes: Vec<String> = on_date
+        .iter()
+        .map(|class| {
+            let status = session_status(class, date, notices);
+            session_cells(class, status, bells)
+                .iter()
+                .map(|c| c.get_content())
+                .collect::<Vec<_>>()
+                .join(" ─ ")
+        })
+        .collect();
+
+    let Some(longest_len) = classes.iter().map(|x| x.len()).max() else {
+        return format!("NO CLASSES FOR {}\n", day);
+    };
+    let dashes = "─".repeat(longest_len / 2);
+    let mut out = format!("{} CLASSES FOR {} {}\n", dashes, day, dashes);
+    for class in classes {
+        out.push_str(&format!("{}\n", class.bold()));
+    }
+    out.push_str(&format!("─────────────────────{}{}\n", dashes, dashes));
+    out
+}
+
+/// Renders the line of the `next` command: the course, where it takes
+/// place (the meeting link for online classes) and the time left from `now`
+/// if given, e.g. "Lập trình Web 2 (3tc) ─ 707 ─ Sat 23/08 07:00 ─ starts
+/// in 1h 23m". An all-day event shows only its date.
+pub fn next_event_line(event: &Event, now: Option<NaiveDateTime>) -> String {
+    let course = match event.kind {
+        EventKind::Class => event.course.to_string(),
+        EventKind::Exam => format!("Thi {}", event.course),
+    };
+    let place = match &event.online {
+        Some(meeting) => meeting.to_string(),
+        None => event.room.clone(),
+    };
+    if event.all_day {
+        return format!(
+            "{} ─ {} ─ {}",
+            course,
+            place,
+            event.starts_at.format("%a %d/%m")
+        );
+    }
+    let line = format!(
+        "{} ─ {} ─ {}",
+        course,
+        place,
+        event.starts_at.format("%a %d/%m %H:%M")
+    );
+    match now {
+        Some(now) if event.starts_at > now => {
+            format!("{} ─ starts in {}", line, countdown(event.starts_at - now))
+        }
+        Some(_) => format!("{} ─ started", line),
+        None => line,
+    }
+}
+
+/// "2d 3h", "1h 23m", "5m", or "< 1m".
+fn countdown(left: Duration) -> String {
+    let minutes = left.num_minutes();
+    match (minutes / (24 * 60), minutes / 60 % 24, minutes % 60) {
+        (0, 0, 0) => "< 1m".to_string(),
+        (0, 0, m) => format!("{}m", m),
+        (0, h, m) => format!("{}h {}m", h, m),
+        (d, h, _) => format!("{}d {}h", d, h),
+    }
+}
+
+/// The classes of [`today_summary`] as a table with the official
+/// timetable's columns, for output formats other than the terminal.
+pub fn today_table(
+    sessions: &[ClassSession],
+    upcoming: &[UpcomingClass],
+    notices: &[CancellationNotice],
+    bells: &BellSchedule,
+    date: NaiveDate,
+) -> Table {
+    let mut table = table_header![TIMETABLE_HEADER];
+    for class in classes_on(sessions, upcoming, date) {
+        let status = session_status(&class, date, notices);
+        table.add_row(Row::new(session_cells(&class, status, bells)));
+    }
+    table
+}
+
+fn date_in_week(today: NaiveDate, weekday: Weekday) -> NaiveDate {
+    let monday = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+    monday + Duration::days(weekday.num_days_from_monday() as i64)
+}
+
+/// Timetable cells for one class, struck through unless `status` is
+/// [`SessionStatus::Unaffected`].
+fn session_cells(class: &ClassSession, status: SessionStatus, bells: &BellSchedule) -> Vec<Cell> {
+    let period = match bells.resolve(class.session, &class.periods) {
+        Some(span) => format!("{} ({})", class.periods, span),
+        None => class.periods.to_string(),
+    };
+    let room = match &class.online {
+        Some(meeting) => {
+            Cell::new(&meeting.to_string()).with_style(Attr::ForegroundColor(color::BRIGHT_CYAN))
+        }
+        None => Cell::new(&class.room),
+    };
+
+    let course = match status {
+        SessionStatus::Unaffected => class.course.to_string(),
+        SessionStatus::Cancelled => format!("{} (nghỉ)", class.course),
+        SessionStatus::Moved(makeup) => {
+            format!("{} (học bù {})", class.course, makeup.format("%d/%m"))
+        }
+    };
+
+    let cells = vec![
+        Cell::new(&weekday_number(class.weekday).to_string()),
+        Cell::new(class.session.label()),
+        Cell::new(&period),
+        room,
+        Cell::new(&course),
+        Cell::new(&class.lecturer),
+        Cell::new(&class.class_group.to_string()),
+    ];
+
+    if status == SessionStatus::Unaffected {
+        return cells;
+    }
+    cells
+        .iter()
+        .map(|c| Cell::new(&c.get_content().strikethrough().to_string()))
+        .collect()
+}
+
+/// The upcoming schedule looks like this:
+///  ┌───────┬─────┬──────────────┬────────────┬──────┬────────┬────────────────────────────────┬───────────────────────┬─────────────────────────┐
+///  │ Buổi  │ Thứ │ Ngày bắt đầu │ Buổi tới   │ Tiết │ Phòng  │ Học phần                       │ Giảng viên            │ Lớp học tập             │
+///  ├───────┼─────┼──────────────┼────────────┼──────┼────────┼────────────────────────────────┼───────────────────────┼─────────────────────────┤
+///  │ Chiều │ 3   │ 19/08/2025   │ 26/08/2025 │ 1-3  │ 703    │ Đồ án công nghệ phần mềm (1tc) │ ThS. Nhiêu Lập Hòa    │                         │
+///  │ Sáng  │ 7   │ 23/08/2025   │ 23/08/2025 │ 1-3  │ Online │ Đa văn hoá (1tc)               │ ThS. Lê Thị Hồng Thúy │ 7203(ST22A,ST22B,GD22A) │
+///  │ Sáng  │ 7   │ 30/08/2025   │ 30/08/2025 │ 1-3  │ 906    │ Công nghệ IOT (3tc)            │ TS. Vương Công Đạt    │                         │
+///  │ Sáng  │ 7   │ 23/08/2025   │ 23/08/2025 │ 4-6  │ 707    │ Lập trình Web 2 (3tc)          │ ĐH. Hồ Xuân Việt      │                         │
+///  └───────┴─────┴──────────────┴────────────┴──────┴────────┴────────────────────────────────┴───────────────────────┴─────────────────────────┘
+///
+/// "Buổi tới" is each class's first meeting on or after `date`.
+pub fn upcoming_schedule_table(classes: &[UpcomingClass], date: NaiveDate) -> Table {
+    let header = [
+        "Buổi",
+        "Thứ",
+        "Ngày bắt đầu",
+        "Buổi tới",
+        "Tiết",
+        "Phòng",
+        "Học phần",
+        "Giảng viên",
+        "Lớp học tập",
+    ];
+    let mut table = table_header!(header);
+
+    for class in classes {
+        table.add_row(Row::new(vec![
+            Cell::new(class.session.label()),
+            Cell::new(&weekday_number(class.weekday).to_string()),
+            Cell::new(&class.start_date.format("%d/%m/%Y").to_string()),
+            Cell::new(
+                &class
+                    .next_on_or_after(date)
+                    .map_or(String::new(), |d| d.format("%d/%m/%Y").to_string()),
+            ),
+            Cell::new(&class.periods.to_string()),
+            Cell::new(&class.room),
+            Cell::new(&class.course.to_string()),
+            Cell::new(&class.lecturer),
+            Cell::new(&class.class_group.to_string()),
+        ]));
+    }
+    table
+}
+
+/// Renders parsed [`ExamEntry`]s, keeping only exams on or after `date`
+/// (normally today). The exam date is highlighted.
+pub fn exam_schedule(exams: &[ExamEntry], date: NaiveDate) -> Table {
+    let header = [
+        "Học kỳ",
+        "Tên học phần",
+        "Số TC",
+        "Ngày thi",
+        "Xuất",
+        "Thời gian thi",
+        "Phòng",
+        "Hình thức",
+    ];
+
+    let mut table = table_header!(header);
+
+    for exam in exams.iter().filter(|e| e.date >= date) {
+        table.add_row(Row::new(vec![
+            Cell::new(&exam.semester),
+            Cell::new(&exam.course.name),
+            Cell::new(&exam.credits.map(|c| c.to_string()).unwrap_or_default()),
+            Cell::new(&exam.date.format("%d/%m/%Y").to_string())
+                .with_style(Attr::Bold)
+                .with_style(Attr::ForegroundColor(color::RED)),
+            Cell::new(&exam.slot),
+            Cell::new(&exam.time_text),
+            Cell::new(&exam.room),
+            Cell::new(&exam.format),
+        ]));
+    }
+    table
+}
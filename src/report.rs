@@ -0,0 +1,421 @@
+use crate::bell::BellSchedule;
+use crate::course::CourseTitle;
+use crate::event::{Event, EventKind};
+use crate::exam::ExamEntry;
+use crate::group::ClassGroup;
+use crate::meeting::{OnlineMeeting, Platform};
+use crate::notice::{match_cancellations, session_status, CancellationNotice, SessionStatus};
+use crate::timetable::{now, ClassSession, Session};
+use crate::upcoming::{classes_on, UpcomingClass};
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime};
+use serde::Serialize;
+use std::fmt;
+
+/// Version of the JSON document written by [`Report::to_json`], described by
+/// `schema/output.schema.json`. It goes up whenever a field is removed,
+/// renamed or changes meaning; new fields may be added without a bump, which
+/// is why the schema leaves its objects open.
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// The parsed pages of one run, for `--format json`.
+///
+/// Dates are written as `YYYY-MM-DD`, times as `HH:MM` and date-times with
+/// the portal's UTC+7 offset, e.g. `2025-08-23T07:00:00+07:00`.
+#[derive(Debug, Serialize)]
+pub struct Report {
+    schema_version: u32,
+    fetched_at: String,
+    /// Day the sections were computed for (`--date`, or today).
+    date: String,
+    pub profiles: Vec<ProfileReport>,
+}
+
+impl Report {
+    /// An empty report for `date`, stamped with the current time.
+    pub fn new(date: NaiveDate) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            fetched_at: iso_datetime(now()),
+            date: iso_date(date),
+            profiles: Vec::new(),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("a report always serializes")
+    }
+}
+
+/// The sections shown for one profile. Sections that weren't asked for or
+/// couldn't be fetched are left out; the latter are listed in `errors`.
+#[derive(Debug, Serialize)]
+pub struct ProfileReport {
+    profile: String,
+    username: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    today: Option<Vec<DatedClass>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    week: Option<Vec<DatedClass>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    upcoming: Option<Vec<Upcoming>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    notices: Option<Vec<Notice>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    exams: Option<Vec<Exam>>,
+    /// `Some(None)` is written as `null`: nothing is coming up.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    next: Option<Option<Next>>,
+    errors: Vec<SectionError>,
+}
+
+impl ProfileReport {
+    pub fn new(profile: &str, username: &str) -> Self {
+        Self {
+            profile: profile.to_string(),
+            username: username.to_string(),
+            today: None,
+            week: None,
+            upcoming: None,
+            notices: None,
+            exams: None,
+            next: None,
+            errors: Vec::new(),
+        }
+    }
+
+    /// Classes on `date`, as in [`today_summary`](crate::today_summary).
+    pub fn today(
+        &mut self,
+        sessions: &[ClassSession],
+        upcoming: &[UpcomingClass],
+        notices: &[CancellationNotice],
+        bells: &BellSchedule,
+        date: NaiveDate,
+    ) {
+        let classes = classes_on(sessions, upcoming, date)
+            .iter()
+            .map(|class| {
+                let status = session_status(class, date, notices);
+                DatedClass::new(class, date, status, bells)
+            })
+            .collect();
+        self.today = Some(classes);
+    }
+
+    /// The official timetable of the week containing `week`.
+    pub fn week(
+        &mut self,
+        sessions: &[ClassSession],
+        notices: &[CancellationNotice],
+        bells: &BellSchedule,
+        week: NaiveDate,
+    ) {
+        let monday = week - Duration::days(week.weekday().num_days_from_monday() as i64);
+        let classes = match_cancellations(sessions, notices, monday, monday + Duration::days(6))
+            .iter()
+            .map(|dated| DatedClass::new(dated.class, dated.date, dated.status, bells))
+            .collect();
+        self.week = Some(classes);
+    }
+
+    /// The upcoming schedule, with each class's next meeting on or after
+    /// `date`.
+    pub fn upcoming(&mut self, classes: &[UpcomingClass], bells: &BellSchedule, date: NaiveDate) {
+        let classes = classes
+            .iter()
+            .map(|class| Upcoming {
+                start_date: iso_date(class.start_date),
+                next_date: class.next_on_or_after(date).map(iso_date),
+                class: Class::new(&class.to_session(), bells),
+            })
+            .collect();
+        self.upcoming = Some(classes);
+    }
+
+    pub fn notices(&mut self, notices: &[CancellationNotice]) {
+        self.notices = Some(notices.iter().map(Notice::from).collect());
+    }
+
+    /// Exams on or after `date`.
+    pub fn exams(&mut self, exams: &[ExamEntry], date: NaiveDate) {
+        let exams = exams
+            .iter()
+            .filter(|e| e.date >= date)
+            .map(Exam::from)
+            .collect();
+        self.exams = Some(exams);
+    }
+
+    pub fn next(&mut self, event: Option<&Event>) {
+        self.next = Some(event.map(Next::from));
+    }
+
+    /// Records that `section` (`profile`, `login`, `today`, `week`, ...)
+    /// couldn't be shown. `code` is the exit code the error maps to.
+    pub fn failed(&mut self, section: &str, code: i32, error: &dyn fmt::Display) {
+        self.errors.push(SectionError {
+            section: section.to_string(),
+            code,
+            message: error.to_string(),
+        });
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct SectionError {
+    section: String,
+    code: i32,
+    message: String,
+}
+
+#[derive(Debug, Serialize)]
+struct Course {
+    name: String,
+    credits: Option<u8>,
+    key: String,
+}
+
+impl From<&CourseTitle> for Course {
+    fn from(course: &CourseTitle) -> Self {
+        Self {
+            name: course.name.clone(),
+            credits: course.credits,
+            key: course.key.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct Group {
+    code: String,
+    cohorts: Vec<String>,
+}
+
+impl From<&ClassGroup> for Group {
+    fn from(group: &ClassGroup) -> Self {
+        Self {
+            code: group.code.clone(),
+            cohorts: group.cohorts.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct Meeting {
+    url: String,
+    platform: &'static str,
+    meeting_id: Option<String>,
+    passcode: Option<String>,
+}
+
+impl From<&OnlineMeeting> for Meeting {
+    fn from(meeting: &OnlineMeeting) -> Self {
+        Self {
+            url: meeting.url.clone(),
+            platform: match meeting.platform {
+                Platform::GoogleMeet => "google_meet",
+                Platform::Zoom => "zoom",
+                Platform::MicrosoftTeams => "microsoft_teams",
+                Platform::Other => "other",
+            },
+            meeting_id: meeting.meeting_id.clone(),
+            passcode: meeting.passcode.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct Periods {
+    start: u8,
+    end: u8,
+}
+
+/// A weekly class. `start_time` and `end_time` are `None` when the bell
+/// schedule doesn't cover its periods.
+#[derive(Debug, Serialize)]
+struct Class {
+    /// ISO weekday, 1 (Monday) to 7 (Sunday).
+    weekday: u32,
+    session: &'static str,
+    periods: Periods,
+    start_time: Option<String>,
+    end_time: Option<String>,
+    room: String,
+    course: Course,
+    lecturer: String,
+    class_group: Group,
+    online: Option<Meeting>,
+}
+
+impl Class {
+    fn new(class: &ClassSession, bells: &BellSchedule) -> Self {
+        let span = bells.resolve(class.session, &class.periods);
+        Self {
+            weekday: class.weekday.number_from_monday(),
+            session: session_name(class.session),
+            periods: Periods {
+                start: class.periods.start,
+                end: class.periods.end,
+            },
+            start_time: span.map(|s| iso_time(s.start)),
+            end_time: span.map(|s| iso_time(s.end)),
+            room: class.room.clone(),
+            course: Course::from(&class.course),
+            lecturer: class.lecturer.clone(),
+            class_group: Group::from(&class.class_group),
+            online: class.online.as_ref().map(Meeting::from),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct DatedClass {
+    date: String,
+    /// `scheduled`, `cancelled` or `moved`.
+    status: &'static str,
+    makeup_date: Option<String>,
+    #[serde(flatten)]
+    class: Class,
+}
+
+impl DatedClass {
+    fn new(
+        class: &ClassSession,
+        date: NaiveDate,
+        status: SessionStatus,
+        bells: &BellSchedule,
+    ) -> Self {
+        let (status, makeup_date) = match status {
+            SessionStatus::Unaffected => ("scheduled", None),
+            SessionStatus::Cancelled => ("cancelled", None),
+            SessionStatus::Moved(makeup) => ("moved", Some(iso_date(makeup))),
+        };
+        Self {
+            date: iso_date(date),
+            status,
+            makeup_date,
+            class: Class::new(class, bells),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct Upcoming {
+    start_date: String,
+    next_date: Option<String>,
+    #[serde(flatten)]
+    class: Class,
+}
+
+#[derive(Debug, Serialize)]
+struct DateSpan {
+    start: String,
+    end: String,
+}
+
+#[derive(Debug, Serialize)]
+struct Notice {
+    time_text: String,
+    content: String,
+    dates: Vec<DateSpan>,
+    session: Option<&'static str>,
+    course: Option<Course>,
+    makeup_date: Option<String>,
+}
+
+impl From<&CancellationNotice> for Notice {
+    fn from(notice: &CancellationNotice) -> Self {
+        Self {
+            time_text: notice.time_text.clone(),
+            content: notice.content.clone(),
+            dates: notice
+                .dates
+                .iter()
+                .map(|d| DateSpan {
+                    start: iso_date(d.start),
+                    end: iso_date(d.end),
+                })
+                .collect(),
+            session: notice.session.map(session_name),
+            course: notice.course.as_ref().map(Course::from),
+            makeup_date: notice.makeup_date.map(iso_date),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct Exam {
+    semester: String,
+    course: Course,
+    date: String,
+    slot: String,
+    start_time: Option<String>,
+    time_text: String,
+    room: String,
+    format: String,
+}
+
+impl From<&ExamEntry> for Exam {
+    fn from(exam: &ExamEntry) -> Self {
+        Self {
+            semester: exam.semester.clone(),
+            course: Course::from(&exam.course),
+            date: iso_date(exam.date),
+            slot: exam.slot.clone(),
+            start_time: exam.start_time.map(iso_time),
+            time_text: exam.time_text.clone(),
+            room: exam.room.clone(),
+            format: exam.format.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct Next {
+    /// `class` or `exam`.
+    kind: &'static str,
+    starts_at: String,
+    ends_at: Option<String>,
+    all_day: bool,
+    course: Course,
+    room: String,
+    online: Option<Meeting>,
+}
+
+impl From<&Event> for Next {
+    fn from(event: &Event) -> Self {
+        Self {
+            kind: match event.kind {
+                EventKind::Class => "class",
+                EventKind::Exam => "exam",
+            },
+            starts_at: iso_datetime(event.starts_at),
+            ends_at: event.ends_at.map(iso_datetime),
+            all_day: event.all_day,
+            course: Course::from(&event.course),
+            room: event.room.clone(),
+            online: event.online.as_ref().map(Meeting::from),
+        }
+    }
+}
+
+fn session_name(session: Session) -> &'static str {
+    match session {
+        Session::Morning => "morning",
+        Session::Afternoon => "afternoon",
+        Session::Evening => "evening",
+    }
+}
+
+fn iso_date(date: NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+fn iso_time(time: NaiveTime) -> String {
+    time.format("%H:%M").to_string()
+}
+
+/// Local portal time, with its UTC+7 offset.
+fn iso_datetime(datetime: NaiveDateTime) -> String {
+    datetime.format("%Y-%m-%dT%H:%M:%S+07:00").to_string()
+}
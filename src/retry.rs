@@ -0,0 +1,161 @@
+use crate::error::Error;
+use rand::Rng;
+use std::env;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// How long portal requests may take and how failed GETs are retried.
+///
+/// A GET that times out, can't connect or gets a 5xx/429 answer is retried
+/// up to `retries` times, waiting `backoff`, then twice that, and so on
+/// (capped at `max_backoff`), each wait scaled by a random factor between
+/// 0.5 and 1.5 so parallel runs don't retry in lockstep. POSTs (login and
+/// postbacks) are never retried as they change the portal's state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Limit for a single request.
+    pub timeout: Duration,
+    /// Extra attempts after the first one.
+    pub retries: u32,
+    /// Wait before the first retry.
+    pub backoff: Duration,
+    pub max_backoff: Duration,
+    /// Limit for everything the client does, counted from when it's built.
+    pub deadline: Option<Duration>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(20),
+            retries: 3,
+            backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(8),
+            deadline: None,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// The default policy, with `UDA_TIMEOUT`, `UDA_RETRIES` and
+    /// `UDA_DEADLINE` applied if they are set (durations in seconds).
+    pub fn from_env() -> Result<Self, Error> {
+        let mut policy = Self::default();
+        if let Ok(s) = env::var("UDA_TIMEOUT") {
+            policy.timeout = parse_seconds(&s).map_err(|e| Error::config("UDA_TIMEOUT", e))?;
+        }
+        if let Ok(s) = env::var("UDA_RETRIES") {
+            policy.retries = s
+                .trim()
+                .parse()
+                .map_err(|_| Error::config("UDA_RETRIES", format!("invalid count `{}`", s)))?;
+        }
+        if let Ok(s) = env::var("UDA_DEADLINE") {
+            policy.deadline =
+                Some(parse_seconds(&s).map_err(|e| Error::config("UDA_DEADLINE", e))?);
+        }
+        Ok(policy)
+    }
+
+    /// Wait before retry number `retry` (1 for the first retry).
+    pub fn delay(&self, retry: u32) -> Duration {
+        let exponential = self
+            .backoff
+            .saturating_mul(2u32.saturating_pow(retry.saturating_sub(1)));
+        exponential
+            .min(self.max_backoff)
+            .mul_f64(rand::thread_rng().gen_range(0.5..1.5))
+    }
+}
+
+/// Parses a positive number of seconds such as "30" or "2.5".
+pub fn parse_seconds(s: &str) -> Result<Duration, String> {
+    s.trim()
+        .trim_end_matches('s')
+        .parse::<f64>()
+        .ok()
+        .filter(|secs| *secs > 0.0)
+        .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+        .ok_or_else(|| format!("invalid duration `{}`, expected seconds", s))
+}
+
+/// Progress of a portal request, passed to the callback set with
+/// [`UdaClientBuilder::on_request`](crate::UdaClientBuilder::on_request).
+#[derive(Debug, Clone, Copy)]
+pub enum RequestEvent<'a> {
+    /// Attempt `attempt` of at most `attempts` is being sent.
+    Sending {
+        page: &'a str,
+        attempt: u32,
+        attempts: u32,
+    },
+    /// An attempt failed with `reason`; the next one starts after `delay`.
+    Retrying {
+        page: &'a str,
+        attempt: u32,
+        attempts: u32,
+        reason: &'a reqwest::Error,
+        delay: Duration,
+    },
+}
+
+impl fmt::Display for RequestEvent<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RequestEvent::Sending {
+                page, attempt: 1, ..
+            } => write!(f, "Loading /sv/{}", page),
+            RequestEvent::Sending {
+                page,
+                attempt,
+                attempts,
+            } => write!(f, "Loading /sv/{} (attempt {}/{})", page, attempt, attempts),
+            RequestEvent::Retrying {
+                page,
+                attempt,
+                attempts,
+                reason,
+                delay,
+            } => write!(
+                f,
+                "Loading /sv/{} failed (attempt {}/{}: {}), retrying in {:.1}s",
+                page,
+                attempt,
+                attempts,
+                short_reason(reason),
+                delay.as_secs_f64()
+            ),
+        }
+    }
+}
+
+pub(crate) fn short_reason(e: &reqwest::Error) -> String {
+    if e.is_timeout() {
+        "timed out".to_string()
+    } else if e.is_connect() {
+        "could not connect".to_string()
+    } else if let Some(status) = e.status() {
+        status.to_string()
+    } else {
+        e.to_string()
+    }
+}
+
+/// Whether a failed GET is worth trying again.
+pub(crate) fn is_transient(e: &reqwest::Error) -> bool {
+    match e.status() {
+        Some(status) => status.is_server_error() || status.as_u16() == 429,
+        None => e.is_timeout() || e.is_connect() || e.is_request() || e.is_body(),
+    }
+}
+
+/// Callback receiving [`RequestEvent`]s.
+#[derive(Clone)]
+pub(crate) struct Reporter(pub(crate) Arc<dyn Fn(RequestEvent<'_>) + Send + Sync>);
+
+impl fmt::Debug for Reporter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Reporter")
+    }
+}
@@ -0,0 +1,199 @@
+use cookie_store::RawCookie;
+use reqwest::cookie::CookieStore;
+use reqwest::header::HeaderValue;
+use reqwest::Url;
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::{self, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+/// Where the session of `username` on the portal at `base_url` is kept
+/// between runs: `$XDG_STATE_HOME/timetable-myuda/cookies-<host>-<username>`,
+/// falling back to `~/.local/state` when `XDG_STATE_HOME` isn't set.
+///
+/// The host (with its port, if any) keeps a mirror or a mock portal from
+/// being sent the real portal's cookies, or overwriting them.
+pub fn default_cookie_path(base_url: &Url, username: &str) -> Option<PathBuf> {
+    let state_dir = env::var_os("XDG_STATE_HOME")
+        .filter(|d| !d.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|h| PathBuf::from(h).join(".local/state")))?;
+    let host = match base_url.port() {
+        Some(port) => format!("{}_{}", base_url.host_str().unwrap_or(""), port),
+        None => base_url.host_str().unwrap_or("").to_string(),
+    };
+    // Unlike the username, the host keeps its dots and dashes, so the last
+    // dash still separates the two
+    let host: String = host
+        .chars()
+        .map(|c| match c {
+            c if c.is_ascii_alphanumeric() || c == '.' || c == '-' => c,
+            _ => '_',
+        })
+        .collect();
+    let name: String = username
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    Some(
+        state_dir
+            .join("timetable-myuda")
+            .join(format!("cookies-{}-{}", host, name)),
+    )
+}
+
+/// The client's cookies. Unlike reqwest's `Jar`, it keeps each cookie's
+/// domain, path and expiry, so a saved session comes back exactly as the
+/// portal set it, and it can be emptied before logging in again.
+#[derive(Debug, Default)]
+pub(crate) struct CookieJar(RwLock<cookie_store::CookieStore>);
+
+impl CookieJar {
+    /// Drops every cookie, e.g. those of an expired session.
+    pub(crate) fn clear(&self) {
+        self.0.write().unwrap().clear();
+    }
+}
+
+impl CookieStore for CookieJar {
+    fn set_cookies(&self, cookie_headers: &mut dyn Iterator<Item = &HeaderValue>, url: &Url) {
+        let cookies = cookie_headers
+            .filter_map(|header| header.to_str().ok())
+            .filter_map(|header| RawCookie::parse(header.to_string()).ok());
+        self.0.write().unwrap().store_response_cookies(cookies, url);
+    }
+
+    fn cookies(&self, url: &Url) -> Option<HeaderValue> {
+        let header = self
+            .0
+            .read()
+            .unwrap()
+            .get_request_values(url)
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect::<Vec<_>>()
+            .join("; ");
+        (!header.is_empty())
+            .then(|| HeaderValue::from_str(&header).ok())
+            .flatten()
+    }
+}
+
+/// Reads the cookies saved in `path`, leaving out expired ones. A missing
+/// file is not an error: there is simply no session to resume.
+pub(crate) fn load_cookies(path: &Path) -> io::Result<CookieJar> {
+    let file = match fs::File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(CookieJar::default()),
+        Err(e) => return Err(e),
+    };
+    let store = cookie_store::CookieStore::load_json(BufReader::new(file))
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    Ok(CookieJar(RwLock::new(store)))
+}
+
+/// Writes the unexpired cookies of `jar` to `path`, one JSON object per
+/// line with its domain, path and expiry. Session cookies are kept too, as
+/// that is what the portal's session is. The file is readable by the current
+/// user only (0600) as it grants portal access.
+pub(crate) fn save_cookies(jar: &CookieJar, path: &Path) -> io::Result<()> {
+    let store = jar.0.read().unwrap();
+    let mut lines = String::new();
+    for cookie in store.iter_unexpired() {
+        let line = serde_json::to_string(cookie)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        lines += &line;
+        lines.push('\n');
+    }
+
+    if let Some(dir) = path.parent() {
+        create_private_dir(dir)?;
+    }
+    private_file(path)?.write_all(lines.as_bytes())
+}
+
+#[cfg(unix)]
+fn create_private_dir(dir: &Path) -> io::Result<()> {
+    use std::os::unix::fs::DirBuilderExt;
+    fs::DirBuilder::new()
+        .recursive(true)
+        .mode(0o700)
+        .create(dir)
+}
+
+#[cfg(not(unix))]
+fn create_private_dir(dir: &Path) -> io::Result<()> {
+    fs::create_dir_all(dir)
+}
+
+#[cfg(unix)]
+fn private_file(path: &Path) -> io::Result<fs::File> {
+    use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+    let file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)?;
+    // `mode` only applies to new files; tighten one left by an older run
+    file.set_permissions(fs::Permissions::from_mode(0o600))?;
+    Ok(file)
+}
+
+#[cfg(not(unix))]
+fn private_file(path: &Path) -> io::Result<fs::File> {
+    OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(jar: &CookieJar, cookie: &str, url: &Url) {
+        let header = HeaderValue::from_str(cookie).unwrap();
+        jar.set_cookies(&mut std::iter::once(&header), url);
+    }
+
+    fn sent(jar: &CookieJar, url: &Url) -> Option<String> {
+        jar.cookies(url).map(|h| h.to_str().unwrap().to_string())
+    }
+
+    #[test]
+    fn saved_session_replaced_after_relogin() {
+        let path = env::temp_dir()
+            .join(format!("timetable-myuda-test-{}", std::process::id()))
+            .join("cookies");
+        let login = Url::parse("https://my.uda.edu.vn/sv/svlogin").unwrap();
+        let tkb = Url::parse("https://my.uda.edu.vn/sv/tkb").unwrap();
+
+        let jar = CookieJar::default();
+        set(&jar, "ASP.NET_SessionId=bad; path=/; HttpOnly", &login);
+        save_cookies(&jar, &path).unwrap();
+
+        // The next run resumes the saved session, which has expired, so it
+        // logs in again
+        let jar = load_cookies(&path).unwrap();
+        assert_eq!(sent(&jar, &tkb).as_deref(), Some("ASP.NET_SessionId=bad"));
+        set(&jar, "ASP.NET_SessionId=ok; path=/; HttpOnly", &login);
+        assert_eq!(sent(&jar, &tkb).as_deref(), Some("ASP.NET_SessionId=ok"));
+        jar.clear();
+        assert_eq!(sent(&jar, &tkb), None);
+        set(&jar, "ASP.NET_SessionId=ok; path=/; HttpOnly", &login);
+        save_cookies(&jar, &path).unwrap();
+
+        let jar = load_cookies(&path).unwrap();
+        assert_eq!(sent(&jar, &tkb).as_deref(), Some("ASP.NET_SessionId=ok"));
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn missing_cookie_file_is_no_session() {
+        let jar = load_cookies(Path::new("/nonexistent/timetable-myuda/cookies")).unwrap();
+        let tkb = Url::parse("https://my.uda.edu.vn/sv/tkb").unwrap();
+        assert_eq!(sent(&jar, &tkb), None);
+    }
+}
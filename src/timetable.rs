@@ -0,0 +1,402 @@
+use crate::course::CourseTitle;
+use crate::error::Error;
+use crate::group::ClassGroup;
+use crate::meeting::OnlineMeeting;
+use chrono::{Datelike, Duration, FixedOffset, NaiveDate, NaiveDateTime, Utc, Weekday};
+use reqwest::Url;
+use scraper::{ElementRef, Html, Selector};
+use std::fmt;
+
+/// Buổi học (part of the day) as shown in the "BUỔI" column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Session {
+    Morning,
+    Afternoon,
+    Evening,
+}
+
+impl Session {
+    /// Parses the portal label ("Sáng", "Chiều", "Tối").
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "sáng" => Some(Session::Morning),
+            "chiều" => Some(Session::Afternoon),
+            "tối" => Some(Session::Evening),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Session::Morning => "Sáng",
+            Session::Afternoon => "Chiều",
+            Session::Evening => "Tối",
+        }
+    }
+}
+
+impl fmt::Display for Session {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+/// Inclusive range of periods ("tiết"), e.g. "1-3" or a single "4".
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PeriodRange {
+    pub start: u8,
+    pub end: u8,
+}
+
+impl PeriodRange {
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+        let (start, end) = match s.split_once('-') {
+            Some((a, b)) => (a.trim().parse().ok()?, b.trim().parse().ok()?),
+            None => {
+                let p = s.parse().ok()?;
+                (p, p)
+            }
+        };
+        if start > end {
+            return None;
+        }
+        Some(Self { start, end })
+    }
+}
+
+impl fmt::Display for PeriodRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.start == self.end {
+            write!(f, "{}", self.start)
+        } else {
+            write!(f, "{}-{}", self.start, self.end)
+        }
+    }
+}
+
+/// One weekly class from the official timetable (`#MainContent_GV2`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClassSession {
+    pub weekday: Weekday,
+    pub session: Session,
+    pub periods: PeriodRange,
+    pub room: String,
+    pub course: CourseTitle,
+    pub lecturer: String,
+    pub class_group: ClassGroup,
+    pub online: Option<OnlineMeeting>,
+}
+
+impl ClassSession {
+    pub fn is_online(&self) -> bool {
+        self.online.is_some() || self.room.to_lowercase().contains("online")
+    }
+}
+
+/// Converts the portal's weekday number ("2" = Monday ... "8" = Sunday).
+pub fn parse_weekday(s: &str) -> Option<Weekday> {
+    match s.trim() {
+        "2" => Some(Weekday::Mon),
+        "3" => Some(Weekday::Tue),
+        "4" => Some(Weekday::Wed),
+        "5" => Some(Weekday::Thu),
+        "6" => Some(Weekday::Fri),
+        "7" => Some(Weekday::Sat),
+        "8" | "CN" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+/// Inverse of [`parse_weekday`], used when rendering the "THỨ" column.
+pub fn weekday_number(weekday: Weekday) -> u8 {
+    weekday.number_from_monday() as u8 + 1
+}
+
+/// Current date in Vietnam (UTC+7), which is what the portal's dates refer to.
+pub fn today() -> NaiveDate {
+    now().date()
+}
+
+/// Current local time in Vietnam (UTC+7), for comparing with class and exam
+/// start times.
+pub fn now() -> NaiveDateTime {
+    let vn_offset = FixedOffset::east_opt(7 * 3600).unwrap();
+    Utc::now().with_timezone(&vn_offset).naive_local()
+}
+
+/// Reads a date given on the command line, relative to `today`:
+///
+/// * `2025-08-23` or `23/08/2025`
+/// * `today`, `tomorrow`, `yesterday`
+/// * `+3d`, `-1d`, `+2w` (days or weeks from today)
+/// * `mon`, `monday` or a portal weekday number such as `2`: the next such
+///   day, today included; `next mon` is the one after today
+pub fn parse_date(s: &str, today: NaiveDate) -> Result<NaiveDate, String> {
+    let invalid = || {
+        format!(
+            "invalid date `{}`, expected YYYY-MM-DD, dd/mm/yyyy, tomorrow, +3d or next mon",
+            s
+        )
+    };
+    let input = s.trim().to_lowercase();
+
+    if let Ok(date) = NaiveDate::parse_from_str(&input, "%Y-%m-%d")
+        .or_else(|_| NaiveDate::parse_from_str(&input, "%d/%m/%Y"))
+    {
+        return Ok(date);
+    }
+    match input.as_str() {
+        "today" => return Ok(today),
+        "tomorrow" => return Ok(today + Duration::days(1)),
+        "yesterday" => return Ok(today - Duration::days(1)),
+        _ => {}
+    }
+
+    if let Some(sign) = input.chars().next().filter(|c| *c == '+' || *c == '-') {
+        let amount = &input[1..];
+        let (number, unit_days) = match (amount.strip_suffix('d'), amount.strip_suffix('w')) {
+            (Some(number), _) => (number, 1),
+            (_, Some(number)) => (number, 7),
+            _ => return Err(invalid()),
+        };
+        let number: i64 = number.parse().map_err(|_| invalid())?;
+        let days = number
+            .checked_mul(unit_days * if sign == '-' { -1 } else { 1 })
+            .and_then(Duration::try_days)
+            .ok_or_else(invalid)?;
+        return today.checked_add_signed(days).ok_or_else(invalid);
+    }
+
+    let (after_today, day) = match input.strip_prefix("next ") {
+        Some(day) => (true, day.trim()),
+        None => (false, input.as_str()),
+    };
+    let weekday = day
+        .parse::<Weekday>()
+        .ok()
+        .or_else(|| parse_weekday(&day.to_uppercase()))
+        .ok_or_else(invalid)?;
+    let mut ahead =
+        (7 + weekday.num_days_from_monday() - today.weekday().num_days_from_monday()) % 7;
+    if after_today && ahead == 0 {
+        ahead = 7;
+    }
+    Ok(today + Duration::days(ahead as i64))
+}
+
+/// The grid matching `selector` on `page`. Only a blank document may lack
+/// it; on any other page a missing grid is an [`Error::Layout`], as that is
+/// what a change to the portal's layout looks like.
+pub(crate) fn find_grid<'a>(
+    html: &'a Html,
+    page: &str,
+    selector: &str,
+) -> Result<Option<ElementRef<'a>>, Error> {
+    let grid = Selector::parse(selector).unwrap();
+    match html.select(&grid).next() {
+        Some(table) => Ok(Some(table)),
+        None if html.root_element().text().all(|t| t.trim().is_empty()) => Ok(None),
+        None => Err(Error::layout(page, selector)),
+    }
+}
+
+/// Collects the rows of a grid that could be read, leaving out those parsed
+/// as `None`. A row that can't be read is left out and its error added to
+/// `skipped`, unless no row at all could be read: then the first error is
+/// returned, as the columns have most likely changed.
+pub(crate) fn read_rows<T>(
+    rows: impl Iterator<Item = Result<Option<T>, Error>>,
+    skipped: &mut Vec<Error>,
+) -> Result<Vec<T>, Error> {
+    let mut read = Vec::new();
+    let mut errors = Vec::new();
+    for row in rows {
+        match row {
+            Ok(Some(row)) => read.push(row),
+            Ok(None) => {}
+            Err(e) => errors.push(e),
+        }
+    }
+    if read.is_empty() && !errors.is_empty() {
+        return Err(errors.swap_remove(0));
+    }
+    skipped.extend(errors);
+    Ok(read)
+}
+
+fn cell_text(cell: &ElementRef) -> String {
+    cell.text().collect::<String>().trim().to_string()
+}
+
+fn online_meeting(cell: &ElementRef, base: &Url) -> Option<OnlineMeeting> {
+    let a_selector = Selector::parse("a").unwrap();
+    let text = cell_text(cell);
+    cell.select(&a_selector).find_map(|link| {
+        let href = link.value().attr("href")?;
+        OnlineMeeting::from_anchor(href, &text, base)
+    })
+}
+
+/// Parses the official timetable (`#MainContent_GV2`) into [`ClassSession`]s.
+///
+/// A page without the table is an [`Error::Layout`]. Rows with a single
+/// cell (empty-data or pager rows) are left out, and so are rows whose
+/// weekday, session or period can't be read; their errors go in `skipped`,
+/// and are an [`Error::Parse`] only if no row could be read.
+///
+/// # Arguments
+///
+/// * `html` - A reference to a parsed HTML document from the `scraper` crate.
+/// * `tr` - A `Selector` for the table rows (`<tr>`) that contain timetable data.
+/// * `td` - A `Selector` for the table data cells (`<td>`) within each selected row.
+/// * `base` - URL of the page, which relative meeting links are resolved against.
+/// * `skipped` - Collects the errors of the rows that were left out.
+pub fn parse_timetable(
+    html: &Html,
+    tr: &Selector,
+    td: &Selector,
+    base: &Url,
+    skipped: &mut Vec<Error>,
+) -> Result<Vec<ClassSession>, Error> {
+    const TABLE: &str = "#MainContent_GV2";
+    let Some(table) = find_grid(html, "tkb", TABLE)? else {
+        return Ok(Vec::new());
+    };
+
+    read_rows(
+        table.select(tr).enumerate().map(|(i, row)| {
+            let cells: Vec<ElementRef> = row.select(td).collect();
+            if cells.len() <= 1 {
+                return Ok(None);
+            }
+            let invalid = |message: String| Error::parse("tkb", TABLE, i + 1, message);
+            if cells.len() < 7 {
+                return Err(invalid(format!("expected 7 cells, found {}", cells.len())));
+            }
+            let texts: Vec<String> = cells.iter().map(cell_text).collect();
+
+            let weekday = parse_weekday(&texts[0])
+                .ok_or_else(|| invalid(format!("unreadable weekday `{}`", texts[0])))?;
+            let session = Session::parse(&texts[1])
+                .ok_or_else(|| invalid(format!("unreadable session `{}`", texts[1])))?;
+            let periods = PeriodRange::parse(&texts[2])
+                .ok_or_else(|| invalid(format!("unreadable periods `{}`", texts[2])))?;
+
+            // The room cell may carry a meeting anchor after the room name
+            let room = cells[3]
+                .text()
+                .map(str::trim)
+                .find(|t| !t.is_empty())
+                .unwrap_or("")
+                .to_string();
+            let online = if texts[3].to_lowercase().contains("online") {
+                online_meeting(&cells[3], base)
+            } else {
+                None
+            };
+
+            Ok(Some(ClassSession {
+                weekday,
+                session,
+                periods,
+                room,
+                course: CourseTitle::parse(&texts[4]),
+                lecturer: texts[5].clone(),
+                class_group: ClassGroup::parse(&texts[6]),
+                online,
+            }))
+        }),
+        skipped,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timetable(body: &str) -> Result<Vec<ClassSession>, Error> {
+        timetable_skipping(body, &mut Vec::new())
+    }
+
+    fn timetable_skipping(
+        body: &str,
+        skipped: &mut Vec<Error>,
+    ) -> Result<Vec<ClassSession>, Error> {
+        let html = Html::parse_document(body);
+        let tr = Selector::parse("tr").unwrap();
+        let td = Selector::parse("td").unwrap();
+        let base = Url::parse("https://my.uda.edu.vn/sv/tkb").unwrap();
+        parse_timetable(&html, &tr, &td, &base, skipped)
+    }
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn parse_date_reads_dates_and_relative_forms() {
+        // A Saturday
+        let today = date("2025-08-23");
+        assert_eq!(parse_date("2025-09-08", today), Ok(date("2025-09-08")));
+        assert_eq!(parse_date("08/09/2025", today), Ok(date("2025-09-08")));
+        assert_eq!(parse_date(" Tomorrow ", today), Ok(date("2025-08-24")));
+        assert_eq!(parse_date("yesterday", today), Ok(date("2025-08-22")));
+        assert_eq!(parse_date("+3d", today), Ok(date("2025-08-26")));
+        assert_eq!(parse_date("-1d", today), Ok(date("2025-08-22")));
+        assert_eq!(parse_date("+2w", today), Ok(date("2025-09-06")));
+    }
+
+    #[test]
+    fn parse_date_reads_weekdays() {
+        let today = date("2025-08-23");
+        assert_eq!(parse_date("mon", today), Ok(date("2025-08-25")));
+        assert_eq!(parse_date("next monday", today), Ok(date("2025-08-25")));
+        // The portal's numbering: 7 is Saturday, CN is Sunday
+        assert_eq!(parse_date("7", today), Ok(today));
+        assert_eq!(parse_date("next 7", today), Ok(date("2025-08-30")));
+        assert_eq!(parse_date("cn", today), Ok(date("2025-08-24")));
+    }
+
+    #[test]
+    fn parse_date_rejects_nonsense() {
+        let today = date("2025-08-23");
+        assert!(parse_date("31/02/2025", today).is_err());
+        assert!(parse_date("+3", today).is_err());
+        assert!(parse_date("+99999999999999w", today).is_err());
+        assert!(parse_date("someday", today).is_err());
+    }
+
+    #[test]
+    fn unreadable_row_is_skipped() {
+        let mut skipped = Vec::new();
+        let sessions = timetable_skipping(
+            r#"<table id="MainContent_GV2"><tr><th>THỨ</th></tr>
+<tr><td>3</td><td>Sáng</td><td>1-3</td><td>906</td><td>Công nghệ IOT (3tc)</td><td>TS. C</td><td></td></tr>
+<tr><td>5</td><td>Khuya</td><td>4-5</td><td>703</td><td>Lập trình Web 2 (3tc)</td><td>ĐH. D</td><td></td></tr>
+</table>"#,
+            &mut skipped,
+        )
+        .unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].weekday, Weekday::Tue);
+        assert!(matches!(skipped.as_slice(), [Error::Parse { row: 3, .. }]));
+    }
+
+    #[test]
+    fn no_readable_row_is_a_parse_error() {
+        let result = timetable(
+            r#"<table id="MainContent_GV2"><tr><th>THỨ</th></tr>
+<tr><td>Sáng</td><td>3</td><td>1-3</td><td>906</td><td>Công nghệ IOT (3tc)</td><td>TS. C</td><td></td></tr>
+</table>"#,
+        );
+        assert!(matches!(result, Err(Error::Parse { row: 2, .. })));
+    }
+
+    #[test]
+    fn missing_grid_is_a_layout_error() {
+        let result = timetable(r#"<div id="MainContent_home">Trang chủ</div>"#);
+        assert!(matches!(result, Err(Error::Layout { .. })));
+        assert_eq!(timetable("").unwrap(), Vec::new());
+    }
+}
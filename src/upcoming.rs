@@ -0,0 +1,164 @@
+use crate::course::CourseTitle;
+use crate::error::Error;
+use crate::group::ClassGroup;
+use crate::timetable::{find_grid, parse_weekday, read_rows, ClassSession, PeriodRange, Session};
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use scraper::{Html, Selector};
+
+/// A class from the upcoming schedule (`#MainContent_GV1`), i.e. a course
+/// that starts on `start_date` and then repeats weekly on `weekday`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UpcomingClass {
+    pub session: Session,
+    pub weekday: Weekday,
+    pub start_date: NaiveDate,
+    pub periods: PeriodRange,
+    pub room: String,
+    pub course: CourseTitle,
+    pub lecturer: String,
+    pub class_group: ClassGroup,
+}
+
+impl UpcomingClass {
+    /// Dated weekly occurrences, starting from the first `weekday` on or after
+    /// `start_date`. The iterator is unbounded; use `take_while` to stop it.
+    pub fn occurrences(&self) -> Occurrences {
+        let offset = (7 + self.weekday.num_days_from_monday()
+            - self.start_date.weekday().num_days_from_monday())
+            % 7;
+        Occurrences {
+            next: Some(self.start_date + Duration::days(offset as i64)),
+        }
+    }
+
+    /// Whether the class meets on `date`.
+    pub fn occurs_on(&self, date: NaiveDate) -> bool {
+        date >= self.start_date && date.weekday() == self.weekday
+    }
+
+    /// First meeting on or after `date`.
+    pub fn next_on_or_after(&self, date: NaiveDate) -> Option<NaiveDate> {
+        self.occurrences().find(|d| *d >= date)
+    }
+
+    /// The class as a timetable entry, for the weeks after it starts.
+    pub fn to_session(&self) -> ClassSession {
+        ClassSession {
+            weekday: self.weekday,
+            session: self.session,
+            periods: self.periods,
+            room: self.room.clone(),
+            course: self.course.clone(),
+            lecturer: self.lecturer.clone(),
+            class_group: self.class_group.clone(),
+            online: None,
+        }
+    }
+}
+
+/// Classes meeting on `date`: the weekly `sessions` of its weekday, plus the
+/// classes of the upcoming schedule that have started by then and aren't
+/// already listed (same course, session and periods).
+pub fn classes_on(
+    sessions: &[ClassSession],
+    upcoming: &[UpcomingClass],
+    date: NaiveDate,
+) -> Vec<ClassSession> {
+    let mut on_date: Vec<ClassSession> = sessions
+        .iter()
+        .filter(|class| class.weekday == date.weekday())
+        .cloned()
+        .collect();
+    for class in upcoming.iter().filter(|class| class.occurs_on(date)) {
+        let listed = on_date.iter().any(|s| {
+            s.course.key == class.course.key
+                && s.session == class.session
+                && s.periods == class.periods
+        });
+        if !listed {
+            on_date.push(class.to_session());
+        }
+    }
+    on_date
+}
+
+/// Iterator returned by [`UpcomingClass::occurrences`].
+#[derive(Debug, Clone)]
+pub struct Occurrences {
+    next: Option<NaiveDate>,
+}
+
+impl Iterator for Occurrences {
+    type Item = NaiveDate;
+
+    fn next(&mut self) -> Option<NaiveDate> {
+        let current = self.next?;
+        self.next = current.checked_add_signed(Duration::weeks(1));
+        Some(current)
+    }
+}
+
+/// Parses the upcoming schedule (`#MainContent_GV1`) into [`UpcomingClass`]es.
+///
+/// A page without the table is an [`Error::Layout`]. Rows with a single
+/// cell are left out, and so are rows whose session, weekday, start date or
+/// period can't be read; their errors go in `skipped`, and are an
+/// [`Error::Parse`] only if no row could be read.
+///
+/// # Arguments
+///
+/// * `html` - A reference to a parsed HTML document from the `scraper` crate.
+/// * `tr` - A `Selector` for the table rows (`<tr>`) of the upcoming schedule.
+/// * `td` - A `Selector` for the table data cells (`<td>`) within each selected row.
+/// * `skipped` - Collects the errors of the rows that were left out.
+pub fn parse_upcoming_schedule(
+    html: &Html,
+    tr: &Selector,
+    td: &Selector,
+    skipped: &mut Vec<Error>,
+) -> Result<Vec<UpcomingClass>, Error> {
+    const TABLE: &str = "#MainContent_GV1";
+    let Some(r) = find_grid(html, "tkb", TABLE)? else {
+        return Ok(Vec::new());
+    };
+
+    read_rows(
+        r.select(tr).enumerate().map(|(i, row)| {
+            let row_data: Vec<String> = row
+                .select(td)
+                .map(|cell| cell.text().collect::<String>().trim().to_string())
+                .collect();
+            if row_data.len() <= 1 {
+                return Ok(None);
+            }
+            let invalid = |message: String| Error::parse("tkb", TABLE, i + 1, message);
+            if row_data.len() < 8 {
+                return Err(invalid(format!(
+                    "expected 8 cells, found {}",
+                    row_data.len()
+                )));
+            }
+
+            let session = Session::parse(&row_data[0])
+                .ok_or_else(|| invalid(format!("unreadable session `{}`", row_data[0])))?;
+            let weekday = parse_weekday(&row_data[1])
+                .ok_or_else(|| invalid(format!("unreadable weekday `{}`", row_data[1])))?;
+            let start_date = NaiveDate::parse_from_str(&row_data[2], "%d/%m/%Y")
+                .map_err(|_| invalid(format!("unreadable start date `{}`", row_data[2])))?;
+            let periods = PeriodRange::parse(&row_data[3])
+                .ok_or_else(|| invalid(format!("unreadable periods `{}`", row_data[3])))?;
+
+            Ok(Some(UpcomingClass {
+                session,
+                weekday,
+                start_date,
+                periods,
+                room: row_data[4].clone(),
+                course: CourseTitle::parse(&row_data[5]),
+                lecturer: row_data[6].clone(),
+                class_group: ClassGroup::parse(&row_data[7]),
+            }))
+        }),
+        skipped,
+    )
+}
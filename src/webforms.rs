@@ -0,0 +1,176 @@
+use scraper::{Html, Selector};
+
+/// Form state of an ASP.NET WebForms page: `__VIEWSTATE`,
+/// `__VIEWSTATEGENERATOR`, `__EVENTVALIDATION` and the other hidden inputs,
+/// plus the current value of every `<select>`.
+///
+/// The portal rejects postbacks that don't send this state back, so a page
+/// is fetched first and its state replayed with the fields being changed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FormState {
+    fields: Vec<(String, String)>,
+}
+
+impl FormState {
+    /// Collects the hidden inputs and selected dropdown values of `html`.
+    pub fn from_html(html: &Html) -> Self {
+        let hidden = Selector::parse("input[type=hidden][name]").unwrap();
+        let select = Selector::parse("select[name]").unwrap();
+        let selected = Selector::parse("option[selected]").unwrap();
+        let option = Selector::parse("option").unwrap();
+
+        let mut state = Self::default();
+        for input in html.select(&hidden) {
+            let el = input.value();
+            state.set(el.attr("name").unwrap(), el.attr("value").unwrap_or(""));
+        }
+        for dropdown in html.select(&select) {
+            // A browser posts the first option when none is marked selected
+            let current = dropdown
+                .select(&selected)
+                .next()
+                .or_else(|| dropdown.select(&option).next());
+            if let Some(current) = current {
+                let value = current
+                    .value()
+                    .attr("value")
+                    .map(str::to_string)
+                    .unwrap_or_else(|| current.text().collect::<String>().trim().to_string());
+                state.set(dropdown.value().attr("name").unwrap(), &value);
+            }
+        }
+        state
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Sets a field, replacing its value if it is already present.
+    pub fn set(&mut self, name: &str, value: &str) {
+        match self.fields.iter_mut().find(|(n, _)| n == name) {
+            Some((_, v)) => *v = value.to_string(),
+            None => self.fields.push((name.to_string(), value.to_string())),
+        }
+    }
+
+    /// Builder-style [`FormState::set`].
+    pub fn with(mut self, name: &str, value: &str) -> Self {
+        self.set(name, value);
+        self
+    }
+
+    /// Fields for a `__doPostBack(target, argument)` call, i.e. this state
+    /// with `__EVENTTARGET` and `__EVENTARGUMENT` set.
+    pub fn postback(&self, target: &str, argument: &str) -> Vec<(String, String)> {
+        self.clone()
+            .with("__EVENTTARGET", target)
+            .with("__EVENTARGUMENT", argument)
+            .fields
+    }
+
+    pub fn fields(&self) -> &[(String, String)] {
+        &self.fields
+    }
+}
+
+/// A `<select>` on a WebForms page, e.g. the portal's week or semester picker.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dropdown {
+    /// Form field name, which is also the postback target of an
+    /// auto-postback dropdown.
+    pub name: String,
+    /// `(value, text)` of each option.
+    pub options: Vec<(String, String)>,
+}
+
+impl Dropdown {
+    /// All named dropdowns on the page.
+    pub fn find_all(html: &Html) -> Vec<Self> {
+        let select = Selector::parse("select[name]").unwrap();
+        let option = Selector::parse("option").unwrap();
+
+        html.select(&select)
+            .map(|dropdown| Self {
+                name: dropdown.value().attr("name").unwrap().to_string(),
+                options: dropdown
+                    .select(&option)
+                    .map(|o| {
+                        let text = o.text().collect::<String>().trim().to_string();
+                        let value = o.value().attr("value").map_or(text.clone(), str::to_string);
+                        (value, text)
+                    })
+                    .collect(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"<form>
+<input type="hidden" name="__VIEWSTATE" value="dDwtMTA4">
+<input type="hidden" name="__EVENTVALIDATION" value="/wEdAAk">
+<input type="hidden" name="__EVENTTARGET" value="">
+<input type="hidden" name="__LASTFOCUS">
+<input type="text" name="txtSearch" value="ignored">
+<select name="ctl00$MainContent$cboHocKy">
+  <option value="20251">HK1 2025-2026</option>
+  <option value="20252" selected="selected">HK2 2025-2026</option>
+</select>
+<select name="ctl00$MainContent$cboTuan">
+  <option value="1">Tuần 1 (04/08/2025 - 10/08/2025)</option>
+  <option>Tuần 2 (11/08/2025 - 17/08/2025)</option>
+</select>
+</form>"#;
+
+    #[test]
+    fn collects_hidden_fields_only() {
+        let state = FormState::from_html(&Html::parse_document(PAGE));
+        assert_eq!(state.get("__VIEWSTATE"), Some("dDwtMTA4"));
+        assert_eq!(state.get("__EVENTVALIDATION"), Some("/wEdAAk"));
+        assert_eq!(state.get("__LASTFOCUS"), Some(""));
+        assert_eq!(state.get("txtSearch"), None);
+    }
+
+    #[test]
+    fn dropdown_posts_selected_or_first_option() {
+        let state = FormState::from_html(&Html::parse_document(PAGE));
+        assert_eq!(state.get("ctl00$MainContent$cboHocKy"), Some("20252"));
+        assert_eq!(state.get("ctl00$MainContent$cboTuan"), Some("1"));
+    }
+
+    #[test]
+    fn postback_overwrites_event_fields() {
+        let state =
+            FormState::from_html(&Html::parse_document(PAGE)).with("__EVENTARGUMENT", "stale");
+        let fields = state.postback("ctl00$MainContent$cboTuan", "");
+        let value = |name: &str| {
+            let matching: Vec<_> = fields.iter().filter(|(n, _)| n == name).collect();
+            assert_eq!(matching.len(), 1, "{} is posted once", name);
+            matching[0].1.clone()
+        };
+        assert_eq!(value("__EVENTTARGET"), "ctl00$MainContent$cboTuan");
+        assert_eq!(value("__EVENTARGUMENT"), "");
+        assert_eq!(value("__VIEWSTATE"), "dDwtMTA4");
+    }
+
+    #[test]
+    fn finds_dropdowns_with_text_as_missing_value() {
+        let dropdowns = Dropdown::find_all(&Html::parse_document(PAGE));
+        assert_eq!(dropdowns.len(), 2);
+        assert_eq!(dropdowns[1].name, "ctl00$MainContent$cboTuan");
+        assert_eq!(
+            dropdowns[1].options[1],
+            (
+                "Tuần 2 (11/08/2025 - 17/08/2025)".to_string(),
+                "Tuần 2 (11/08/2025 - 17/08/2025)".to_string()
+            )
+        );
+    }
+}